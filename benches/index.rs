@@ -0,0 +1,118 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use scribe::index::ScribeIndex;
+use scribe::path::ScribePath;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const NOTES: usize = 2000;
+const FUZZY_NOTES: usize = 50_000;
+const FOLDERS: [&str; 5] = ["inbox", "projects", "reference", "journal", "archive"];
+
+// Builds a vault of linked, tagged notes spread over a few nested folders
+fn generate_vault(root: &Path) {
+    let _ = fs::remove_dir_all(root);
+    for i in 0..NOTES {
+        let folder = FOLDERS[i % FOLDERS.len()];
+        let dir = root.join(folder).join(format!("{:02}", i % 20));
+        fs::create_dir_all(&dir).unwrap();
+
+        let next = (i + 1) % NOTES;
+        let data = format!(
+            "---\ntitle: Note {i}\ntags: [\"{folder}\", \"bench/{group}\"]\ndate: 2026-01-{day:02}\n---\n\n# Note {i}\n\nSee [[{next_folder}/{next_group:02}/note_{next}]] and [the previous](note_{prev}.md) #topic/{topic}.\n\n- [ ] follow up 📅 2026-02-{day:02}\n- [x] drafted\n\nstatus:: active\n\n## Details\n\n{body}\n",
+            group = i % 20,
+            day = i % 28 + 1,
+            next_folder = FOLDERS[next % FOLDERS.len()],
+            next_group = next % 20,
+            prev = i.saturating_sub(1),
+            topic = i % 7,
+            body = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20),
+        );
+        fs::write(dir.join(format!("note_{i}.md")), data).unwrap();
+    }
+}
+
+fn vault_dir() -> PathBuf {
+    let root = env::temp_dir().join("scribe_bench_vault");
+    generate_vault(&root);
+
+    // The config is read once, so the vault must be set before the first index
+    env::set_var("SCRIBE_DIRECTORY", &root);
+    root
+}
+
+fn bench_index(c: &mut Criterion) {
+    let root = vault_dir();
+
+    let mut group = c.benchmark_group("index");
+    group.sample_size(10);
+
+    group.bench_function("full", |b| {
+        b.iter(|| {
+            let mut index = ScribeIndex::new();
+            index.index(None);
+            index
+        })
+    });
+
+    let mut index = ScribeIndex::new();
+    index.index(None);
+    group.bench_function("refresh_unchanged", |b| b.iter(|| index.refresh()));
+
+    // Single changes, which only re-resolve the links they can affect
+    let existing = ScribePath::from(&index.notes[NOTES / 2].path);
+    group.bench_function("update_one", |b| b.iter(|| index.update(&existing)));
+
+    let added_file = root.join("inbox").join("added.md");
+    fs::write(
+        &added_file,
+        "# Added\n\nSee [[note_5]] and [[note_6]] #topic/1\n",
+    )
+    .unwrap();
+    let added = ScribePath::from(&added_file.display().to_string());
+    group.bench_function("insert_and_delete", |b| {
+        b.iter(|| {
+            index.insert(&added);
+            index.delete(&added);
+        })
+    });
+    let _ = fs::remove_file(added_file);
+
+    // Backlinks for every note, as when rendering them across the whole vault
+    let paths: Vec<ScribePath> = index
+        .notes
+        .iter()
+        .map(|note| ScribePath::from(&note.path))
+        .collect();
+    group.bench_function("backlinks_all", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .map(|path| index.get_backlinks(path).len())
+                .sum::<usize>()
+        })
+    });
+
+    // Copies of the vault under distinct folders, to match the size of a large vault
+    let mut large = ScribeIndex::new();
+    for copy in 0..FUZZY_NOTES / NOTES {
+        for note in &index.notes {
+            let mut note = note.clone();
+            note.path = note.path.replacen(
+                &root.display().to_string(),
+                &format!("{}/copy_{}", root.display(), copy),
+                1,
+            );
+            large.notes.push(note);
+        }
+    }
+    group.bench_function("fuzzy_find_50k", |b| {
+        b.iter(|| large.fuzzy_find("proj note 12", 20))
+    });
+
+    group.finish();
+    let _ = fs::remove_dir_all(root);
+}
+
+criterion_group!(benches, bench_index);
+criterion_main!(benches);
@@ -1,13 +1,28 @@
 use config::Config;
+use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::fs;
 
+lazy_static! {
+    static ref CONFIG: ScribeConfig = ScribeConfig::read();
+}
+
+#[derive(Clone)]
 pub struct ScribeConfig {
     _config: HashMap<String, String>,
 }
 
 impl ScribeConfig {
+    // The environment is read once per process, later calls share the same values
     pub fn load() -> Self {
+        CONFIG.clone()
+    }
+
+    pub fn global() -> &'static Self {
+        &CONFIG
+    }
+
+    fn read() -> Self {
         let cfg: Config = Config::builder()
             .add_source(config::Environment::with_prefix("SCRIBE"))
             .build()
@@ -0,0 +1,305 @@
+use crate::parsers::parser::Parser;
+use crate::path::ScribePath;
+use std::time::Duration;
+
+// Scores follow fzf, a match is worth more after a boundary and inside a run
+const SCORE_MATCH: i64 = 16;
+const SCORE_GAP_START: i64 = -3;
+const SCORE_GAP_EXTENSION: i64 = -1;
+const BONUS_BOUNDARY_WHITE: i64 = 10;
+const BONUS_BOUNDARY_DELIMITER: i64 = 9;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+// Worth about one and a half matched characters for a note edited today, half that after a week
+const RECENCY_BONUS: f64 = 24.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    White,
+    Delimiter,
+    NonWord,
+    Lower,
+    Upper,
+    Number,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        match c {
+            c if c.is_lowercase() => CharClass::Lower,
+            c if c.is_uppercase() => CharClass::Upper,
+            c if c.is_numeric() => CharClass::Number,
+            c if c.is_alphabetic() => CharClass::Lower,
+            c if c.is_whitespace() => CharClass::White,
+            '/' | ',' | ':' | ';' | '|' => CharClass::Delimiter,
+            _ => CharClass::NonWord,
+        }
+    }
+
+    fn bonus(previous: Self, current: Self) -> i64 {
+        let word = !matches!(
+            current,
+            CharClass::White | CharClass::Delimiter | CharClass::NonWord
+        );
+        match (previous, current) {
+            (CharClass::White, _) if word => BONUS_BOUNDARY_WHITE,
+            (CharClass::Delimiter, _) if word => BONUS_BOUNDARY_DELIMITER,
+            (CharClass::NonWord, _) if word => BONUS_BOUNDARY,
+            (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL,
+            (previous, CharClass::Number) if previous != CharClass::Number => BONUS_CAMEL,
+            (_, CharClass::White) => BONUS_BOUNDARY_WHITE,
+            (_, CharClass::Delimiter | CharClass::NonWord) => BONUS_BOUNDARY,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FuzzyScore {
+    pub score: i64,
+    // Byte offsets of the matched characters
+    pub positions: Vec<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FuzzyField {
+    Title,
+    Alias,
+    Path,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FuzzyMatch {
+    pub path: ScribePath,
+    pub score: i64,
+    // The title, alias or relative path that matched best, with `positions` into it
+    pub field: FuzzyField,
+    pub text: String,
+    pub positions: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    pub fn recency_bonus(age: Duration) -> i64 {
+        let days = age.as_secs_f64() / 86400.0;
+        (RECENCY_BONUS / (1.0 + days / RECENCY_HALF_LIFE_DAYS)).round() as i64
+    }
+}
+
+// Space separated terms must all match, case only matters when the query has capitals
+pub struct FuzzyQuery {
+    terms: Vec<Vec<char>>,
+    case_sensitive: bool,
+}
+
+impl FuzzyQuery {
+    pub fn new(query: &str) -> Self {
+        let query = Parser::normalize(query);
+        let case_sensitive = query.chars().any(|c| c.is_uppercase());
+        let terms = query
+            .split_whitespace()
+            .map(|term| {
+                term.chars()
+                    .map(|c| Self::fold(c, case_sensitive))
+                    .collect()
+            })
+            .collect();
+        FuzzyQuery {
+            terms,
+            case_sensitive,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    fn fold(c: char, case_sensitive: bool) -> char {
+        match case_sensitive {
+            true => c,
+            false if c.is_ascii() => c.to_ascii_lowercase(),
+            false => c.to_lowercase().next().unwrap_or(c),
+        }
+    }
+
+    pub fn score(&self, text: &str) -> Option<FuzzyScore> {
+        if self.terms.is_empty() || !self.matches(text) {
+            return None;
+        }
+
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let folded: Vec<char> = chars
+            .iter()
+            .map(|(_, c)| Self::fold(*c, self.case_sensitive))
+            .collect();
+
+        let mut total = FuzzyScore {
+            score: 0,
+            positions: vec![],
+        };
+        for term in &self.terms {
+            let (start, end) = Self::window(&folded, term)?;
+            let (score, positions) = Self::score_window(&chars, &folded, term, start, end);
+            total.score += score;
+            total
+                .positions
+                .extend(positions.into_iter().map(|i| chars[i].0));
+        }
+        total.positions.sort_unstable();
+        total.positions.dedup();
+        Some(total)
+    }
+
+    // Whether every term appears in order, checked without allocating as most texts fail here
+    pub fn matches(&self, text: &str) -> bool {
+        self.terms.iter().all(|term| {
+            let mut next = 0;
+            for c in text.chars() {
+                if Self::fold(c, self.case_sensitive) == term[next] {
+                    next += 1;
+                    if next == term.len() {
+                        return true;
+                    }
+                }
+            }
+            false
+        })
+    }
+
+    // The first match found scanning forward, tightened by scanning back from its end
+    fn window(text: &[char], term: &[char]) -> Option<(usize, usize)> {
+        let mut next = 0;
+        let mut end = None;
+        for (i, c) in text.iter().enumerate() {
+            if *c == term[next] {
+                next += 1;
+                if next == term.len() {
+                    end = Some(i + 1);
+                    break;
+                }
+            }
+        }
+        let end = end?;
+
+        let mut remaining = term.len();
+        let mut start = 0;
+        for i in (0..end).rev() {
+            if text[i] == term[remaining - 1] {
+                remaining -= 1;
+                if remaining == 0 {
+                    start = i;
+                    break;
+                }
+            }
+        }
+        Some((start, end))
+    }
+
+    fn score_window(
+        chars: &[(usize, char)],
+        text: &[char],
+        term: &[char],
+        start: usize,
+        end: usize,
+    ) -> (i64, Vec<usize>) {
+        let mut score = 0;
+        let mut positions: Vec<usize> = vec![];
+        let mut next = 0;
+        let mut in_gap = false;
+        let mut consecutive = 0;
+        let mut first_bonus = 0;
+        let mut previous = match start {
+            0 => CharClass::White,
+            _ => CharClass::of(chars[start - 1].1),
+        };
+
+        for i in start..end {
+            let class = CharClass::of(chars[i].1);
+            if next < term.len() && text[i] == term[next] {
+                positions.push(i);
+                score += SCORE_MATCH;
+                let mut bonus = CharClass::bonus(previous, class);
+                if consecutive == 0 {
+                    first_bonus = bonus;
+                } else {
+                    if bonus >= BONUS_BOUNDARY && bonus > first_bonus {
+                        first_bonus = bonus;
+                    }
+                    bonus = bonus.max(first_bonus).max(BONUS_CONSECUTIVE);
+                }
+                score += match next {
+                    0 => bonus * BONUS_FIRST_CHAR_MULTIPLIER,
+                    _ => bonus,
+                };
+                in_gap = false;
+                consecutive += 1;
+                next += 1;
+            } else {
+                score += match in_gap {
+                    true => SCORE_GAP_EXTENSION,
+                    false => SCORE_GAP_START,
+                };
+                in_gap = true;
+                consecutive = 0;
+                first_bonus = 0;
+            }
+            previous = class;
+        }
+        (score, positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, text: &str) -> Option<i64> {
+        FuzzyQuery::new(query).score(text).map(|found| found.score)
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let found = FuzzyQuery::new("mtg").score("Weekly Meeting").unwrap();
+        assert_eq!(found.positions, vec![7, 10, 13]);
+        assert!(score("xyz", "Weekly Meeting").is_none());
+
+        // Contiguous beats scattered, boundaries beat the middle of words
+        assert!(score("meet", "meeting notes") > score("meet", "my eerie etc t"));
+        assert!(score("note", "projects/notes") > score("note", "keynotes"));
+        assert!(score("sp", "scribe project") > score("sp", "inspect"));
+
+        // Capitals in the query make it case sensitive
+        assert!(score("Scribe", "scribe").is_none());
+        assert!(score("scribe", "SCRIBE").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_score_terms() {
+        let found = FuzzyQuery::new("inbox größe").score("international/Größe in the inbox");
+        assert!(found.is_some());
+        assert!(score("inbox missing", "international/inbox").is_none());
+        assert!(FuzzyQuery::new("   ").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_recency_bonus() {
+        let day = Duration::from_secs(86400);
+        assert_eq!(FuzzyMatch::recency_bonus(Duration::ZERO), 24);
+        assert_eq!(FuzzyMatch::recency_bonus(day * 7), 12);
+        assert!(FuzzyMatch::recency_bonus(day * 365) < 1);
+    }
+
+    #[test]
+    fn test_fuzzy_window() {
+        // The first match is kept, scanning back only moves its start closer
+        let text: Vec<char> = "a_b_c abc".chars().collect();
+        let term: Vec<char> = "abc".chars().collect();
+        assert_eq!(FuzzyQuery::window(&text, &term), Some((0, 5)));
+
+        let text: Vec<char> = "xa_abc".chars().collect();
+        assert_eq!(FuzzyQuery::window(&text, &term), Some((3, 6)));
+    }
+}
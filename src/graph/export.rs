@@ -0,0 +1,328 @@
+use crate::index::ScribeIndex;
+use crate::parsers::parser::Parser;
+use crate::parsers::tags::Tags;
+use crate::path::ScribePath;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Tableau 10, assigned to categories in name order and reused past ten
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+const TAG_COLOR: &str = "#d4d4d4";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+// Which notes to export and how, the default exports every note without tags or colours
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ExportOptions {
+    // Notes tagged with any of these, or a tag nested beneath them
+    pub tags: Vec<String>,
+    // Notes under any of these folders, relative to the vault
+    pub folders: Vec<String>,
+    pub color_by_category: bool,
+    pub include_tags: bool,
+}
+
+impl ExportOptions {
+    fn includes(&self, relative: &str, tags: &BTreeSet<&String>) -> bool {
+        let in_folder = self.folders.is_empty()
+            || self.folders.iter().any(|folder| {
+                let folder = Parser::normalize(folder.trim_matches('/'));
+                folder.is_empty()
+                    || relative
+                        .strip_prefix(&folder)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            });
+        let tagged = self.tags.is_empty()
+            || self
+                .tags
+                .iter()
+                .any(|prefix| tags.iter().any(|tag| Tags::is_under(tag, prefix)));
+        in_folder && tagged
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Note,
+    Tag,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ExportNode {
+    // The relative path for notes, `#tag` for tags
+    pub id: String,
+    pub label: String,
+    pub kind: NodeKind,
+    pub category: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ExportLink {
+    pub source: String,
+    pub target: String,
+    // What the link points at, another note or one of the note's tags
+    pub kind: NodeKind,
+}
+
+// The nodes and links shape D3 force layouts expect, also written out as DOT or GraphML
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct GraphExport {
+    pub nodes: Vec<ExportNode>,
+    pub links: Vec<ExportLink>,
+}
+
+impl GraphExport {
+    pub fn build(index: &ScribeIndex, options: &ExportOptions) -> Self {
+        let mut notes: Vec<(String, ExportNode, BTreeSet<&String>)> = vec![];
+        for note in &index.notes {
+            let path = ScribePath::from(&note.path);
+            let relative = Parser::normalize(&path.as_string(false));
+            let tags: BTreeSet<&String> = note.tags.iter().flatten().collect();
+            if !options.includes(&relative, &tags) {
+                continue;
+            }
+
+            let label = note.title.clone().unwrap_or_else(|| {
+                let name = relative.rsplit('/').next().unwrap_or_default();
+                name.trim_end_matches(".md").to_string()
+            });
+            let node = ExportNode {
+                id: relative,
+                label,
+                kind: NodeKind::Note,
+                category: Some(path.get_category()),
+                color: None,
+            };
+            notes.push((note.path.clone(), node, tags));
+        }
+        notes.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+
+        let mut export = GraphExport::default();
+        let ids: BTreeSet<String> = notes.iter().map(|(_, node, _)| node.id.clone()).collect();
+        let mut tags: BTreeSet<String> = BTreeSet::new();
+        for (path, node, note_tags) in &notes {
+            for target in index.link_graph().outgoing(&ScribePath::from(path)) {
+                let target = Parser::normalize(&target.as_string(false));
+                if ids.contains(&target) {
+                    export.links.push(ExportLink {
+                        source: node.id.clone(),
+                        target,
+                        kind: NodeKind::Note,
+                    });
+                }
+            }
+            if options.include_tags {
+                for tag in note_tags {
+                    tags.insert(tag.to_string());
+                    export.links.push(ExportLink {
+                        source: node.id.clone(),
+                        target: format!("#{}", tag),
+                        kind: NodeKind::Tag,
+                    });
+                }
+            }
+        }
+
+        let mut notes: Vec<ExportNode> = notes.into_iter().map(|(_, node, _)| node).collect();
+        if options.color_by_category {
+            let categories: BTreeSet<String> = notes
+                .iter()
+                .filter_map(|node| node.category.clone())
+                .collect();
+            let colors: BTreeMap<String, &str> = categories
+                .into_iter()
+                .enumerate()
+                .map(|(i, category)| (category, PALETTE[i % PALETTE.len()]))
+                .collect();
+            for node in notes.iter_mut() {
+                node.color = node
+                    .category
+                    .as_ref()
+                    .map(|category| colors[category].to_string());
+            }
+        }
+        export.nodes = notes;
+
+        for tag in tags {
+            export.nodes.push(ExportNode {
+                id: format!("#{}", tag),
+                label: format!("#{}", tag),
+                kind: NodeKind::Tag,
+                category: None,
+                color: Some(TAG_COLOR.to_string()).filter(|_| options.color_by_category),
+            });
+        }
+        export
+    }
+
+    pub fn write(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Dot => self.to_dot(),
+            ExportFormat::GraphMl => self.to_graphml(),
+            ExportFormat::Json => self.to_json(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut dot = String::from("digraph scribe {\n");
+        for node in &self.nodes {
+            let mut attributes = vec![format!("label={}", quote(&node.label))];
+            if node.kind == NodeKind::Tag {
+                attributes.push("shape=box".to_string());
+            }
+            if let Some(color) = &node.color {
+                attributes.push(format!("style=filled, fillcolor={}", quote(color)));
+            }
+            dot.push_str(&format!(
+                "  {} [{}];\n",
+                quote(&node.id),
+                attributes.join(", ")
+            ));
+        }
+        for link in &self.links {
+            let style = match link.kind {
+                NodeKind::Note => "",
+                NodeKind::Tag => " [style=dashed]",
+            };
+            dot.push_str(&format!(
+                "  {} -> {}{};\n",
+                quote(&link.source),
+                quote(&link.target),
+                style
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let escape = |text: &str| {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+                .replace('\'', "&apos;")
+        };
+        let kind = |kind: NodeKind| match kind {
+            NodeKind::Note => "note",
+            NodeKind::Tag => "tag",
+        };
+
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"category\" for=\"node\" attr.name=\"category\" attr.type=\"string\"/>\n",
+            "  <key id=\"color\" for=\"node\" attr.name=\"color\" attr.type=\"string\"/>\n",
+            "  <key id=\"link_kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <graph id=\"scribe\" edgedefault=\"directed\">\n",
+        ));
+        for node in &self.nodes {
+            xml.push_str(&format!("    <node id=\"{}\">\n", escape(&node.id)));
+            let mut data = vec![("label", node.label.as_str()), ("kind", kind(node.kind))];
+            data.extend(
+                node.category
+                    .as_deref()
+                    .map(|category| ("category", category)),
+            );
+            data.extend(node.color.as_deref().map(|color| ("color", color)));
+            for (key, value) in data {
+                xml.push_str(&format!(
+                    "      <data key=\"{}\">{}</data>\n",
+                    key,
+                    escape(value)
+                ));
+            }
+            xml.push_str("    </node>\n");
+        }
+        for link in &self.links {
+            xml.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"link_kind\">{}</data>\n    </edge>\n",
+                escape(&link.source),
+                escape(&link.target),
+                kind(link.kind)
+            ));
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_export() -> GraphExport {
+        GraphExport {
+            nodes: vec![
+                ExportNode {
+                    id: "inbox/a.md".to_string(),
+                    label: "Say \"hi\" & <wave>".to_string(),
+                    kind: NodeKind::Note,
+                    category: Some("inbox".to_string()),
+                    color: Some(PALETTE[0].to_string()),
+                },
+                ExportNode {
+                    id: "#work".to_string(),
+                    label: "#work".to_string(),
+                    kind: NodeKind::Tag,
+                    category: None,
+                    color: None,
+                },
+            ],
+            links: vec![ExportLink {
+                source: "inbox/a.md".to_string(),
+                target: "#work".to_string(),
+                kind: NodeKind::Tag,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_export_dot() {
+        let dot = test_export().to_dot();
+        assert!(dot.starts_with("digraph scribe {\n"));
+        assert!(dot.contains(
+            "  \"inbox/a.md\" [label=\"Say \\\"hi\\\" & <wave>\", style=filled, fillcolor=\"#4e79a7\"];\n"
+        ));
+        assert!(dot.contains("  \"#work\" [label=\"#work\", shape=box];\n"));
+        assert!(dot.contains("  \"inbox/a.md\" -> \"#work\" [style=dashed];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_export_graphml() {
+        let xml = test_export().to_graphml();
+        assert!(xml.contains("<graph id=\"scribe\" edgedefault=\"directed\">"));
+        assert!(xml.contains("<data key=\"label\">Say &quot;hi&quot; &amp; &lt;wave&gt;</data>"));
+        assert!(xml.contains("<data key=\"category\">inbox</data>"));
+        assert!(xml.contains("<edge source=\"inbox/a.md\" target=\"#work\">"));
+        assert_eq!(xml.matches("<node ").count(), 2);
+    }
+
+    #[test]
+    fn test_export_json() {
+        let json: serde_json::Value = serde_json::from_str(&test_export().to_json()).unwrap();
+        assert_eq!(json["nodes"][0]["id"], "inbox/a.md");
+        assert_eq!(json["nodes"][1]["kind"], "tag");
+        assert_eq!(json["links"][0]["source"], "inbox/a.md");
+        assert_eq!(json["links"][0]["target"], "#work");
+    }
+}
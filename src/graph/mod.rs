@@ -0,0 +1,373 @@
+pub mod export;
+
+use crate::index::NoteInfo;
+use crate::parsers::parser::Parser;
+use crate::path::ScribePath;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+// PageRank parameters, the usual damping with a tolerance well below display precision
+const DAMPING: f64 = 0.85;
+const TOLERANCE: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 100;
+
+// Notes and the resolved links between them, keyed by NFC normalized absolute path
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LinkGraph {
+    // Every note has an entry in both, even without links
+    forward: BTreeMap<String, BTreeSet<String>>,
+    reverse: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl LinkGraph {
+    pub fn from_notes(notes: &[NoteInfo]) -> Self {
+        let mut graph = LinkGraph::default();
+        for note in notes {
+            graph.insert(&note.path, Self::targets(note));
+        }
+        graph
+    }
+
+    // The notes a note links to, links to itself and unresolved links are left out
+    pub fn targets(note: &NoteInfo) -> BTreeSet<String> {
+        note.resolved_links
+            .iter()
+            .flatten()
+            .filter_map(|link| link.resolution.note())
+            .filter(|target| *target != note.path)
+            .map(|target| target.to_string())
+            .collect()
+    }
+
+    fn key(path: &ScribePath) -> String {
+        Parser::normalize(&path.as_string(true))
+    }
+
+    // Adds a note, replacing any links it had before
+    pub fn insert(&mut self, source: &str, targets: BTreeSet<String>) {
+        let source = Parser::normalize(source);
+        let targets: BTreeSet<String> = targets
+            .iter()
+            .map(|target| Parser::normalize(target))
+            .collect();
+
+        let previous = self.forward.remove(&source).unwrap_or_default();
+        for target in previous.difference(&targets) {
+            if let Some(sources) = self.reverse.get_mut(target) {
+                sources.remove(&source);
+            }
+        }
+        for target in &targets {
+            self.forward.entry(target.clone()).or_default();
+            self.reverse
+                .entry(target.clone())
+                .or_default()
+                .insert(source.clone());
+        }
+        self.reverse.entry(source.clone()).or_default();
+        self.forward.insert(source, targets);
+    }
+
+    // Drops a note along with the links to and from it
+    pub fn remove(&mut self, path: &str) {
+        let path = Parser::normalize(path);
+        for target in self.forward.remove(&path).unwrap_or_default() {
+            if let Some(sources) = self.reverse.get_mut(&target) {
+                sources.remove(&path);
+            }
+        }
+        for source in self.reverse.remove(&path).unwrap_or_default() {
+            if let Some(targets) = self.forward.get_mut(&source) {
+                targets.remove(&path);
+            }
+        }
+    }
+
+    pub fn contains(&self, path: &ScribePath) -> bool {
+        self.forward.contains_key(&Self::key(path))
+    }
+
+    // The notes `path` links to
+    pub fn outgoing(&self, path: &ScribePath) -> Vec<ScribePath> {
+        Self::paths(self.forward.get(&Self::key(path)).into_iter().flatten())
+    }
+
+    // The notes linking to `path`
+    pub fn incoming(&self, path: &ScribePath) -> Vec<ScribePath> {
+        Self::paths(self.reverse.get(&Self::key(path)).into_iter().flatten())
+    }
+
+    // Notes within `hops` links of `path` in either direction, with their distance, nearest first
+    pub fn neighbourhood(&self, path: &ScribePath, hops: usize) -> Vec<(ScribePath, usize)> {
+        let start = Self::key(path);
+        let Some(start) = self.forward.get_key_value(&start).map(|(key, _)| key) else {
+            return vec![];
+        };
+
+        let mut distances: BTreeMap<&String, usize> = BTreeMap::from([(start, 0)]);
+        let mut queue: VecDeque<(&String, usize)> = VecDeque::from([(start, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == hops {
+                continue;
+            }
+            for next in self.forward[current].iter().chain(&self.reverse[current]) {
+                if !distances.contains_key(next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        let mut found: Vec<(&String, usize)> = distances.into_iter().collect();
+        found.sort_by_key(|(path, distance)| (*distance, *path));
+        found
+            .into_iter()
+            .map(|(path, distance)| (ScribePath::from(path), distance))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    fn paths<'a>(paths: impl Iterator<Item = &'a String>) -> Vec<ScribePath> {
+        paths.map(|path| ScribePath::from(path)).collect()
+    }
+
+    // Notes with no links in or out
+    pub fn orphans(&self) -> Vec<ScribePath> {
+        Self::paths(
+            self.forward
+                .iter()
+                .filter(|(path, targets)| targets.is_empty() && self.reverse[*path].is_empty())
+                .map(|(path, _)| path),
+        )
+    }
+
+    // Notes with no outgoing links, orphans included
+    pub fn dead_ends(&self) -> Vec<ScribePath> {
+        Self::paths(
+            self.forward
+                .iter()
+                .filter(|(_, targets)| targets.is_empty())
+                .map(|(path, _)| path),
+        )
+    }
+
+    // The most linked to notes with their backlink counts, ties broken by path
+    pub fn hubs(&self, limit: usize) -> Vec<(ScribePath, usize)> {
+        let mut hubs: Vec<(&String, usize)> = self
+            .reverse
+            .iter()
+            .map(|(path, sources)| (path, sources.len()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        hubs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        hubs.into_iter()
+            .take(limit)
+            .map(|(path, count)| (ScribePath::from(path), count))
+            .collect()
+    }
+
+    // Groups of notes linked in either direction, largest first
+    pub fn components(&self) -> Vec<Vec<ScribePath>> {
+        let mut seen: BTreeSet<&String> = BTreeSet::new();
+        let mut components: Vec<Vec<&String>> = vec![];
+        for start in self.forward.keys() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component: Vec<&String> = vec![];
+            let mut stack = vec![start];
+            while let Some(path) = stack.pop() {
+                component.push(path);
+                for next in self.forward[path].iter().chain(&self.reverse[path]) {
+                    if seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(b[0])));
+        components
+            .into_iter()
+            .map(|component| Self::paths(component.into_iter()))
+            .collect()
+    }
+
+    // Importance scores summing to one, highest first, notes without links share theirs evenly
+    pub fn page_rank(&self) -> Vec<(ScribePath, f64)> {
+        let count = self.forward.len();
+        if count == 0 {
+            return vec![];
+        }
+        let paths: Vec<&String> = self.forward.keys().collect();
+        let ids: BTreeMap<&String, usize> =
+            paths.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+        let incoming: Vec<Vec<usize>> = paths
+            .iter()
+            .map(|path| {
+                self.reverse[*path]
+                    .iter()
+                    .map(|source| ids[source])
+                    .collect()
+            })
+            .collect();
+        let outgoing: Vec<usize> = paths.iter().map(|path| self.forward[*path].len()).collect();
+
+        let n = count as f64;
+        let mut ranks = vec![1.0 / n; count];
+        for _ in 0..MAX_ITERATIONS {
+            let dangling: f64 = (0..count)
+                .filter(|i| outgoing[*i] == 0)
+                .map(|i| ranks[i])
+                .sum();
+            let base = (1.0 - DAMPING) / n + DAMPING * dangling / n;
+            let next: Vec<f64> = incoming
+                .iter()
+                .map(|sources| {
+                    base + DAMPING
+                        * sources
+                            .iter()
+                            .map(|j| ranks[*j] / outgoing[*j] as f64)
+                            .sum::<f64>()
+                })
+                .collect();
+            let delta: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+            ranks = next;
+            if delta < TOLERANCE {
+                break;
+            }
+        }
+
+        let mut scores: Vec<(&String, f64)> = paths.into_iter().zip(ranks).collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        scores
+            .into_iter()
+            .map(|(path, score)| (ScribePath::from(path), score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -> b -> c -> a, d -> b, f -> g and e on its own
+    fn test_graph() -> LinkGraph {
+        let mut graph = LinkGraph::default();
+        let links = [
+            ("a", vec!["b"]),
+            ("b", vec!["c"]),
+            ("c", vec!["a"]),
+            ("d", vec!["b"]),
+            ("e", vec![]),
+            ("f", vec!["g"]),
+        ];
+        for (source, targets) in links {
+            let targets = targets.into_iter().map(absolute).collect();
+            graph.insert(&absolute(source), targets);
+        }
+        graph
+    }
+
+    fn absolute(name: &str) -> String {
+        ScribePath::from(name).as_string(true)
+    }
+
+    fn paths(names: &[&str]) -> Vec<ScribePath> {
+        names.iter().map(|name| ScribePath::from(name)).collect()
+    }
+
+    #[test]
+    fn test_graph_orphans_and_dead_ends() {
+        let graph = test_graph();
+        assert_eq!(graph.len(), 7);
+        assert_eq!(graph.orphans(), paths(&["e"]));
+        assert_eq!(graph.dead_ends(), paths(&["e", "g"]));
+    }
+
+    #[test]
+    fn test_graph_hubs() {
+        let graph = test_graph();
+        assert_eq!(
+            graph.hubs(2),
+            vec![(ScribePath::from("b"), 2), (ScribePath::from("a"), 1)]
+        );
+        assert_eq!(graph.hubs(10).len(), 4);
+    }
+
+    #[test]
+    fn test_graph_components() {
+        let graph = test_graph();
+        assert_eq!(
+            graph.components(),
+            vec![
+                paths(&["a", "b", "c", "d"]),
+                paths(&["f", "g"]),
+                paths(&["e"])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_page_rank() {
+        let graph = test_graph();
+        let ranks = graph.page_rank();
+        assert_eq!(ranks.len(), 7);
+        let total: f64 = ranks.iter().map(|(_, score)| score).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        // The cycle collects rank, the note only linking out keeps the least
+        let order: Vec<ScribePath> = ranks.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(order[0], ScribePath::from("b"));
+        assert!(ranks[0].1 > ranks[6].1);
+        assert!(LinkGraph::default().page_rank().is_empty());
+    }
+
+    #[test]
+    fn test_graph_adjacency() {
+        let mut graph = test_graph();
+        let b = ScribePath::from("b");
+        assert_eq!(graph.outgoing(&b), paths(&["c"]));
+        assert_eq!(graph.incoming(&b), paths(&["a", "d"]));
+
+        graph.remove(&b.as_string(true));
+        assert!(!graph.contains(&b));
+        assert!(graph.outgoing(&ScribePath::from("a")).is_empty());
+        assert!(graph.incoming(&ScribePath::from("c")).is_empty());
+        assert_eq!(graph.orphans(), paths(&["d", "e"]));
+    }
+
+    #[test]
+    fn test_graph_neighbourhood() {
+        let graph = test_graph();
+        let d = ScribePath::from("d");
+        let near: Vec<(ScribePath, usize)> = graph.neighbourhood(&d, 2);
+        assert_eq!(
+            near,
+            vec![
+                (d.clone(), 0),
+                (ScribePath::from("b"), 1),
+                (ScribePath::from("a"), 2),
+                (ScribePath::from("c"), 2),
+            ]
+        );
+        assert_eq!(graph.neighbourhood(&d, 0), vec![(d, 0)]);
+        assert!(graph.neighbourhood(&ScribePath::from("z"), 3).is_empty());
+    }
+
+    #[test]
+    fn test_graph_insert_replaces_links() {
+        let mut graph = test_graph();
+        graph.insert(&absolute("d"), BTreeSet::from([absolute("e")]));
+        assert_eq!(graph.hubs(1), vec![(ScribePath::from("a"), 1)]);
+        assert!(graph.orphans().is_empty());
+    }
+}
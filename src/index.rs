@@ -1,36 +1,203 @@
-use crate::parsers::embedded_links::EmbeddedLinks;
+use crate::config::ScribeConfig;
+use crate::fuzzy::{FuzzyField, FuzzyMatch, FuzzyQuery, FuzzyScore};
+use crate::graph::export::{ExportFormat, ExportOptions, GraphExport};
+use crate::graph::LinkGraph;
+use crate::parsers::attachments::{Attachment, Attachments};
+use crate::parsers::block_ids::BlockIds;
+use crate::parsers::callouts::{Callout, Callouts};
+use crate::parsers::embedded_links::{EmbeddedLinks, WikiLink};
+use crate::parsers::footnotes::{Footnote, Footnotes};
+use crate::parsers::front_matter::{FieldValue, FrontMatter};
+use crate::parsers::headings::{Heading, Headings};
+use crate::parsers::inline_fields::{InlineField, InlineFields};
 use crate::parsers::internal_links::InternalLinks;
+use crate::parsers::markdown::Markdown;
+use crate::parsers::parser::Parser;
+use crate::parsers::registry::{NoteParser, ParserRegistry};
+use crate::parsers::span::{Located, Span};
+use crate::parsers::tasks::{Task, Tasks};
 use crate::parsers::title::Title;
 use crate::parsers::web_links::WebLinks;
 use crate::parsers::{date::Date, tags::Tags};
 use crate::path::ScribePath;
-use chrono::{DateTime, Local};
+use crate::query::{Query, QueryError};
+use crate::resolver::{
+    AttachmentFiles, BrokenLink, LinkKind, LinkProblem, LinkResolver, Resolution, ResolvedLink,
+};
+use crate::search::snippet::Snippet;
+use crate::search::{Document, SearchIndex, SearchQuery, SearchResult};
+use crate::storage::json::JsonStorage;
+use crate::storage::sqlite::SqliteStorage;
+use crate::storage::{IndexStorage, StorageError};
+use chrono::{DateTime, Local, NaiveDate};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use twox_hash::XxHash3_64;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct NoteSpans {
+    pub tags: Vec<Located<String>>,
+    pub embedded_links: Vec<Located<WikiLink>>,
+    pub internal_links: Vec<Located<String>>,
+    pub web_links: Vec<Located<String>>,
+}
+
+impl NoteSpans {
+    pub fn from_markdown(markdown: &Markdown) -> Self {
+        Self {
+            tags: Tags::locate(markdown),
+            embedded_links: EmbeddedLinks::locate(markdown),
+            internal_links: InternalLinks::locate(markdown),
+            web_links: WebLinks::locate(markdown),
+        }
+    }
+}
+
+// What a note was parsed from, so unchanged files can be skipped on refresh
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FileStamp {
+    pub mtime: u64,
+    pub size: u64,
+    pub hash: u64,
+}
+
+impl FileStamp {
+    pub fn new(path: &ScribePath, data: &str) -> Self {
+        let metadata = std::fs::metadata(path.as_string(true)).ok();
+        let mtime = metadata
+            .as_ref()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos() as u64);
+        FileStamp {
+            mtime,
+            size: data.len() as u64,
+            hash: XxHash3_64::oneshot(data.as_bytes()),
+        }
+    }
+
+    // Compares filesystem metadata only, without reading the file
+    pub fn is_current(&self, path: &ScribePath) -> bool {
+        let Ok(metadata) = std::fs::metadata(path.as_string(true)) else {
+            return false;
+        };
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos() as u64);
+        self.mtime == mtime && self.size == metadata.len()
+    }
+
+    // Stamps describe the same note when the content matches, mtime only lets refresh skip reading
+    pub fn same_content(&self, other: &Self) -> bool {
+        self.size == other.size && self.hash == other.hash
+    }
+}
+
+enum Refreshed {
+    Added(Box<NoteInfo>, Option<Document>),
+    Updated(usize, Box<NoteInfo>, Option<Document>),
+    Touched(usize, FileStamp),
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct RefreshSummary {
+    pub added: Vec<ScribePath>,
+    pub updated: Vec<ScribePath>,
+    pub removed: Vec<ScribePath>,
+    pub unchanged: usize,
+}
+
+impl RefreshSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct NoteInfo {
     pub path: String,
     pub title: Option<String>,
     pub tags: Option<HashSet<String>>,
     pub date: Option<DateTime<Local>>,
-    pub embedded_links: Option<HashSet<String>>,
+    pub embedded_links: Option<HashSet<WikiLink>>,
     pub internal_links: Option<HashSet<String>>,
     pub web_links: Option<HashSet<String>>,
+    #[serde(default)]
+    pub frontmatter: Option<FrontMatter>,
+    #[serde(default)]
+    pub spans: Option<NoteSpans>,
+    #[serde(default)]
+    pub headings: Option<Vec<Heading>>,
+    #[serde(default)]
+    pub tasks: Option<Vec<Task>>,
+    #[serde(default)]
+    pub inline_fields: Option<Vec<InlineField>>,
+    #[serde(default)]
+    pub attachments: Option<Vec<Attachment>>,
+    #[serde(default)]
+    pub callouts: Option<Vec<Callout>>,
+    #[serde(default)]
+    pub footnotes: Option<Vec<Footnote>>,
+    #[serde(default)]
+    pub block_ids: Option<Vec<Located<String>>>,
+    #[serde(default)]
+    pub created: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub modified: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub stamp: Option<FileStamp>,
+    // Filled in by the index, which knows every note a link could point at
+    #[serde(default)]
+    pub resolved_links: Option<Vec<ResolvedLink>>,
 }
 
 impl NoteInfo {
     pub fn parse(path: &ScribePath) -> Self {
-        let data = path.get_data();
-        let note: Self;
-        if data.is_some() {
-            let file_data = data.unwrap();
-            let title = Title::parse(&file_data);
-            let tags = Tags::parse(&file_data);
-            let date = Date::parse(&file_data);
-            let embedded_links = EmbeddedLinks::parse(&file_data);
-            let internal_links = InternalLinks::parse(&file_data);
-            let web_links = WebLinks::parse(&file_data);
+        Self::parse_with(path, &ParserRegistry::default())
+    }
+
+    pub fn parse_with(path: &ScribePath, parsers: &ParserRegistry) -> Self {
+        Self::parse_data(path, path.get_data().as_deref(), parsers)
+    }
+
+    // Parses the contents already read from `path`
+    pub fn parse_data(path: &ScribePath, data: Option<&str>, parsers: &ParserRegistry) -> Self {
+        let markdown = data.map(Markdown::parse);
+        Self::parse_markdown(path, markdown.as_ref(), parsers)
+    }
+
+    // Parses a note from its markdown, so callers that also need the markdown parse it once
+    pub fn parse_markdown(
+        path: &ScribePath,
+        markdown: Option<&Markdown>,
+        parsers: &ParserRegistry,
+    ) -> Self {
+        let mut note: Self;
+        if let Some(markdown) = markdown {
+            let file_data = markdown.data;
+            let title = Title::from_markdown(markdown);
+            let tags = Tags::from_markdown(markdown);
+            let date = Date::from_markdown(markdown);
+            let embedded_links = EmbeddedLinks::from_markdown(markdown);
+            let internal_links = InternalLinks::from_markdown(markdown);
+            let web_links = WebLinks::from_markdown(markdown);
+            let frontmatter = markdown.front_matter.clone();
+            let spans = Some(NoteSpans::from_markdown(markdown));
+            let headings = Headings::from_markdown(markdown);
+            let tasks = Tasks::from_markdown(markdown);
+            let inline_fields = InlineFields::from_markdown(markdown);
+            let attachments = Attachments::from_markdown(markdown);
+            let callouts = Callouts::from_markdown(markdown);
+            let footnotes = Footnotes::from_markdown(markdown);
+            let block_ids = BlockIds::from_markdown(markdown);
+            let extra = parsers.parse(markdown);
+            let stamp = Some(FileStamp::new(path, file_data));
 
             note = NoteInfo {
                 path: path.as_string(true),
@@ -40,7 +207,29 @@ impl NoteInfo {
                 embedded_links,
                 internal_links,
                 web_links,
-            }
+                frontmatter,
+                spans,
+                headings,
+                tasks,
+                inline_fields,
+                attachments,
+                callouts,
+                footnotes,
+                block_ids,
+                created: None,
+                modified: None,
+                extra,
+                stamp,
+                resolved_links: None,
+            };
+
+            let metadata = note.metadata();
+            let field_date = |keys: &[&str]| {
+                keys.iter()
+                    .find_map(|key| metadata.get(key).and_then(|value| value.as_date()))
+            };
+            note.created = field_date(&["created", "date created"]).or(note.date);
+            note.modified = field_date(&["modified", "updated", "date modified"]);
         } else {
             note = NoteInfo {
                 path: path.as_string(true),
@@ -50,27 +239,154 @@ impl NoteInfo {
                 embedded_links: None,
                 internal_links: None,
                 web_links: None,
+                frontmatter: None,
+                spans: None,
+                headings: None,
+                tasks: None,
+                inline_fields: None,
+                attachments: None,
+                callouts: None,
+                footnotes: None,
+                block_ids: None,
+                created: None,
+                modified: None,
+                extra: BTreeMap::new(),
+                stamp: None,
+                resolved_links: None,
             }
         }
 
         return note;
     }
 
+    pub fn embeds(&self) -> impl Iterator<Item = &WikiLink> {
+        self.embedded_links
+            .iter()
+            .flatten()
+            .filter(|link| link.is_embed)
+    }
+
+    pub fn has_block(&self, id: &str) -> bool {
+        self.block_ids
+            .iter()
+            .flatten()
+            .any(|block| block.value == id)
+    }
+
+    pub fn get_heading(&self, reference: &str) -> Option<&Heading> {
+        let headings = self.headings.as_ref()?;
+        headings.iter().find(|heading| heading.matches(reference))
+    }
+
+    // Front matter merged with inline `key:: value` fields and registered parser output
+    pub fn metadata(&self) -> FrontMatter {
+        let mut metadata = self.frontmatter.clone().unwrap_or_default();
+        for field in self.inline_fields.iter().flatten() {
+            metadata.merge(&field.key, field.value.clone());
+        }
+        for (key, value) in &self.extra {
+            metadata.merge(key, FieldValue::from_json(value.clone()));
+        }
+        metadata
+    }
+
+    // The same value `metadata()` has for `key`, without merging every other field
+    pub fn get_field(&self, key: &str) -> Option<FieldValue> {
+        let mut found = self
+            .frontmatter
+            .as_ref()
+            .and_then(|frontmatter| frontmatter.get(key))
+            .cloned();
+        let inline = self
+            .inline_fields
+            .iter()
+            .flatten()
+            .filter(|field| field.key == key)
+            .map(|field| field.value.clone());
+        let extra = self
+            .extra
+            .get(key)
+            .map(|value| FieldValue::from_json(value.clone()));
+        for value in inline.chain(extra) {
+            found = Some(match found {
+                None => value,
+                Some(existing) => existing.append(value),
+            });
+        }
+        found
+    }
+
+    // Alternative names from the `aliases` (or `alias`) front matter field
+    pub fn aliases(&self) -> Vec<&str> {
+        let Some(frontmatter) = &self.frontmatter else {
+            return vec![];
+        };
+        let Some(value) = frontmatter.get("aliases").or(frontmatter.get("alias")) else {
+            return vec![];
+        };
+        match value.as_list() {
+            Some(list) => list.iter().filter_map(|alias| alias.as_str()).collect(),
+            None => value.as_str().into_iter().collect(),
+        }
+    }
+
+    // Falls back to the file's creation, then modification, time
+    pub fn created_at(&self) -> Option<DateTime<Local>> {
+        let path = ScribePath::from(&self.path);
+        self.created
+            .or_else(|| path.created())
+            .or_else(|| path.modified())
+    }
+
+    pub fn modified_at(&self) -> Option<DateTime<Local>> {
+        self.modified
+            .or_else(|| ScribePath::from(&self.path).modified())
+    }
+
+    // Saved indexes may hold paths relative to the vault, so they load the same on any machine
+    pub fn rebase(&mut self) {
+        let absolute = |path: &mut String| {
+            if !std::path::Path::new(path.as_str()).is_absolute() {
+                *path = ScribePath::from(path).as_string(true);
+            }
+        };
+        absolute(&mut self.path);
+        for link in self.resolved_links.iter_mut().flatten() {
+            match &mut link.resolution {
+                Resolution::Note(path) | Resolution::File(path) => absolute(path),
+                Resolution::Ambiguous(paths) => paths.iter_mut().for_each(absolute),
+                Resolution::Missing => {}
+            }
+        }
+    }
+
     pub fn has_backlink(&self, path: &ScribePath) -> bool {
+        // Resolved links also cover bare names, relative paths and aliases
+        if let Some(links) = &self.resolved_links {
+            let path = Parser::normalize(&path.as_string(true));
+            return links.iter().any(|link| {
+                link.resolution
+                    .note()
+                    .is_some_and(|note| Parser::normalize(note) == path)
+            });
+        }
+
         if self.internal_links.is_some() {
             let links = self.internal_links.as_ref().unwrap();
-            if links.contains(&path.as_string(false)) {
+            if links.contains(&Parser::normalize(&path.as_string(false))) {
                 return true;
-            } else if links.contains(&path.as_string(true)) {
+            } else if links.contains(&Parser::normalize(&path.as_string(true))) {
                 return false;
             }
         }
 
-        if self.embedded_links.is_some() {
-            let links = self.embedded_links.as_ref().unwrap();
-            if links.contains(&path.as_string(false).replace(".md", "")) {
-                return true;
-            } else if links.contains(&path.as_string(true).replace(".md", "")) {
+        if let Some(links) = &self.embedded_links {
+            let relative = Parser::normalize(&path.as_string(false).replace(".md", ""));
+            let absolute = Parser::normalize(&path.as_string(true).replace(".md", ""));
+            let linked = links
+                .iter()
+                .any(|link| link.target_path() == relative || link.target_path() == absolute);
+            if linked {
                 return true;
             }
         }
@@ -79,28 +395,100 @@ impl NoteInfo {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct TagNode {
+    pub name: String,
+    pub tag: String,
+    // Notes tagged with this tag or any tag nested beneath it
+    pub count: usize,
+    pub children: Vec<TagNode>,
+}
+
+impl TagNode {
+    fn build(prefix: &str, notes: &BTreeMap<String, BTreeSet<usize>>) -> Vec<TagNode> {
+        let mut nodes: Vec<TagNode> = vec![];
+        for (tag, paths) in notes {
+            let name = match prefix.is_empty() {
+                true => tag.as_str(),
+                false => match tag.strip_prefix(prefix).and_then(|t| t.strip_prefix('/')) {
+                    Some(name) => name,
+                    None => continue,
+                },
+            };
+            if name.contains('/') {
+                continue;
+            }
+
+            nodes.push(TagNode {
+                name: name.to_string(),
+                tag: tag.clone(),
+                count: paths.len(),
+                children: Self::build(tag, notes),
+            });
+        }
+        nodes
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct ScribeIndex {
     pub notes: Vec<NoteInfo>,
+    #[serde(skip)]
+    pub parsers: ParserRegistry,
+    #[serde(skip)]
+    pub storage: Option<Box<dyn IndexStorage>>,
+    #[serde(skip)]
+    pub search_index: SearchIndex,
+    #[serde(skip)]
+    pub graph: LinkGraph,
+    #[serde(skip)]
+    resolver: LinkResolver,
+    #[serde(skip)]
+    files: AttachmentFiles,
+    #[serde(skip)]
+    positions: HashMap<String, usize>,
 }
 
 impl ScribeIndex {
     pub fn new() -> Self {
         let notes: Vec<NoteInfo> = vec![];
-        return Self { notes };
+        let parsers = ParserRegistry::default();
+        return Self {
+            notes,
+            parsers,
+            storage: None,
+            search_index: SearchIndex::default(),
+            graph: LinkGraph::default(),
+            resolver: LinkResolver::default(),
+            files: AttachmentFiles::new(),
+            positions: HashMap::new(),
+        };
+    }
+
+    // Loads the notes kept in `storage`, which then receives every later change
+    pub fn open<S: IndexStorage + 'static>(storage: S) -> Result<Self, StorageError> {
+        let mut index = Self::new();
+        index.notes = storage.load()?;
+        let saved = storage.load_documents().unwrap_or_default();
+        index.storage = Some(Box::new(storage));
+        index.restore_search(saved);
+        index.link_all();
+        Ok(index)
     }
     pub fn load(index_path: Option<ScribePath>) -> Option<Self> {
-        let data: Option<String>;
-        if index_path.is_none() {
-            data = Self::get_location().get_data();
-        } else {
-            data = index_path.unwrap().get_data();
-        }
+        let location = index_path.unwrap_or_else(Self::get_location);
+        let data = location.get_data();
 
         if data.is_some() {
-            let index = serde_json::from_str(&data.unwrap());
+            let index = serde_json::from_str::<Self>(&data.unwrap());
             if index.is_ok() {
-                return Some(index.unwrap());
+                let mut index = index.unwrap();
+                index.notes.iter_mut().for_each(NoteInfo::rebase);
+                index.files = AttachmentFiles::new();
+                let saved = JsonStorage::new(location.as_pathbuf()).load_documents();
+                index.restore_search(saved.unwrap_or_default());
+                index.link_all();
+                return Some(index);
             }
         }
         return None;
@@ -114,146 +502,1896 @@ impl ScribeIndex {
             root = ScribePath::root();
         }
 
-        for file in root.get_children() {
-            if file.is_markdown() & !file.is_template() & !file.is_tmp() {
-                // Parse and Analyze Note
-                let note = NoteInfo::parse(&file);
-                self.notes.push(note);
+        // Parse and Analyze Notes, in walk order
+        let files = Self::note_files(&root);
+        let parsed: Vec<(NoteInfo, Option<Document>)> = files
+            .par_iter()
+            .map(|file| Self::parse_note(file, &self.parsers))
+            .collect();
+        for (note, document) in parsed {
+            if let Some(document) = document {
+                self.search_index.add(&note.path, document);
             }
+            self.notes.push(note);
         }
-    }
 
-    fn get_location() -> ScribePath {
-        let mut root = ScribePath::root();
-        root.extend(".scribe");
-        return root;
+        // Storage gets every note in one batch once links are resolved, not a row at a time
+        let storage = self.storage.take();
+        self.link_all();
+        self.storage = storage;
+        if let Some(storage) = &self.storage {
+            _ = storage.upsert_all(&self.notes);
+            _ = storage.upsert_documents(self.search_index.documents());
+        }
     }
 
-    pub fn write(&self) {
-        let json_str = serde_json::to_string_pretty(&self).unwrap();
-        _ = std::fs::write(Self::get_location().as_string(true), json_str);
+    // Resolves every link against the whole vault, after indexing or loading
+    fn link_all(&mut self) {
+        self.reposition();
+        self.resolver = LinkResolver::new(&self.notes);
+        let (resolver, files) = (&self.resolver, &self.files);
+        let resolved: Vec<Option<Vec<ResolvedLink>>> = self
+            .notes
+            .par_iter()
+            .map(|note| resolver.resolve_note(note, files))
+            .collect();
+
+        for (i, links) in resolved.into_iter().enumerate() {
+            let path = self.notes[i].path.clone();
+            self.resolver
+                .track(&path, links.as_deref().unwrap_or_default());
+            if self.notes[i].resolved_links != links {
+                self.notes[i].resolved_links = links;
+                self.persist(i);
+            }
+        }
+        self.graph = LinkGraph::from_notes(&self.notes);
     }
 
-    pub fn delete(&mut self, path: &ScribePath) {
-        if self.notes.len() > 0 {
-            for i in 0..self.notes.len() - 1 {
-                if self.notes[i].path == path.as_string(true) {
-                    self.notes.remove(i);
+    // Brings the resolver in line with the notes at `paths`, just added, updated or deleted, then
+    // re-resolves them and any note with a link looked up under a name or alias they changed
+    fn relink(&mut self, paths: &[String]) {
+        let mut affected: BTreeSet<String> = BTreeSet::new();
+        for path in paths {
+            let changed = match self.position(path) {
+                Some(i) => {
+                    affected.insert(path.clone());
+                    self.resolver.insert(&self.notes[i])
                 }
+                None => {
+                    self.graph.remove(path);
+                    self.resolver.remove(path)
+                }
+            };
+            affected.extend(self.resolver.linking(&changed));
+        }
+        self.resolve_affected(affected, paths);
+    }
+
+    // Re-resolves the notes linking to a file just added or removed
+    fn relink_files(&mut self, names: &BTreeSet<String>) {
+        let affected = self.resolver.linking(names);
+        self.resolve_affected(affected, &[]);
+    }
+
+    fn resolve_affected(&mut self, affected: BTreeSet<String>, paths: &[String]) {
+        let positions: Vec<usize> = affected
+            .iter()
+            .filter_map(|path| self.position(path))
+            .collect();
+        let (resolver, files, notes) = (&self.resolver, &self.files, &self.notes);
+        let resolved: Vec<(usize, Option<Vec<ResolvedLink>>)> = positions
+            .into_par_iter()
+            .map(|i| (i, resolver.resolve_note(&notes[i], files)))
+            .collect();
+
+        for (i, links) in resolved {
+            let path = self.notes[i].path.clone();
+            self.resolver
+                .track(&path, links.as_deref().unwrap_or_default());
+            let changed = self.notes[i].resolved_links != links;
+            if changed {
+                self.notes[i].resolved_links = links;
+                self.persist(i);
+            }
+            if changed || paths.contains(&path) {
+                let note = &self.notes[i];
+                self.graph.insert(&note.path, LinkGraph::targets(note));
             }
         }
     }
 
-    fn in_index(&self, path: &ScribePath) -> bool {
+    fn reposition(&mut self) {
+        self.positions = self
+            .notes
+            .iter()
+            .enumerate()
+            .map(|(i, note)| (note.path.clone(), i))
+            .collect();
+    }
+
+    // Where the note at `path` sits in `notes`, which is public, so a stale position falls back
+    // to a scan
+    fn position(&self, path: &str) -> Option<usize> {
+        let found = self.positions.get(path).copied();
+        if found.is_some_and(|i| self.notes.get(i).is_some_and(|note| note.path == path)) {
+            return found;
+        }
+        if found.is_none() && self.positions.len() == self.notes.len() {
+            return None;
+        }
+        self.notes.iter().position(|note| note.path == path)
+    }
+
+    // Resolves a link as if it were written as `[[target]]` in the note at `from`
+    pub fn resolve_link(&self, from: &ScribePath, target: &str) -> Resolution {
+        self.resolver
+            .resolve(&from.as_string(true), LinkKind::Wiki, target)
+    }
+
+    // Links to a note, heading or block that doesn't exist, in note order
+    pub fn broken_links(&self) -> Vec<BrokenLink<'_>> {
+        let notes: HashMap<&str, &NoteInfo> = self
+            .notes
+            .iter()
+            .map(|note| (note.path.as_str(), note))
+            .collect();
+
+        let mut broken: Vec<BrokenLink> = vec![];
         for note in &self.notes {
-            if note.path == path.as_string(true) {
-                return true;
+            for link in note.resolved_links.iter().flatten() {
+                let problem = match &link.resolution {
+                    Resolution::Missing => Some(LinkProblem::MissingNote),
+                    Resolution::Ambiguous(_) | Resolution::File(_) => None,
+                    Resolution::Note(path) => notes.get(path.as_str()).and_then(|target| {
+                        // Nested references such as `#Part#Section` name the last heading
+                        let heading = link.heading.as_deref().and_then(|h| h.rsplit('#').next());
+                        if heading.is_some_and(|heading| target.get_heading(heading).is_none()) {
+                            Some(LinkProblem::MissingHeading)
+                        } else if link
+                            .block_id
+                            .as_ref()
+                            .is_some_and(|id| !target.has_block(id))
+                        {
+                            Some(LinkProblem::MissingBlock)
+                        } else {
+                            None
+                        }
+                    }),
+                };
+                if let Some(problem) = problem {
+                    broken.push(BrokenLink {
+                        source: ScribePath::from(&note.path),
+                        link,
+                        problem,
+                    });
+                }
             }
         }
-        return false;
+        broken
     }
 
-    pub fn update(&mut self, path: &ScribePath) {
-        for i in 0..self.notes.len() {
-            if self.notes[i].path == path.as_string(true) {
-                let new_note = NoteInfo::parse(&path);
-                self.notes[i] = new_note;
+    // Links that match more than one note, such as a bare name shared by notes in two folders
+    pub fn ambiguous_links(&self) -> Vec<(ScribePath, &ResolvedLink)> {
+        let mut links: Vec<(ScribePath, &ResolvedLink)> = vec![];
+        for note in &self.notes {
+            for link in note.resolved_links.iter().flatten() {
+                if let Resolution::Ambiguous(_) = link.resolution {
+                    links.push((ScribePath::from(&note.path), link));
+                }
             }
         }
+        links
     }
 
-    pub fn get_backlinks(&self, path: &ScribePath) -> Vec<ScribePath> {
-        let mut links: Vec<ScribePath> = vec![];
+    // Reads a note once for both its info and its search document
+    fn parse_note(path: &ScribePath, parsers: &ParserRegistry) -> (NoteInfo, Option<Document>) {
+        let data = path.get_data();
+        Self::parse_document(path, data.as_deref(), parsers)
+    }
+
+    fn parse_document(
+        path: &ScribePath,
+        data: Option<&str>,
+        parsers: &ParserRegistry,
+    ) -> (NoteInfo, Option<Document>) {
+        let markdown = data.map(Markdown::parse);
+        let note = NoteInfo::parse_markdown(path, markdown.as_ref(), parsers);
+        let document =
+            markdown.map(|markdown| Document::from_markdown(&markdown, note.title.as_deref()));
+        (note, document)
+    }
+
+    // Uses the saved search documents, only reading notes saved without one
+    fn restore_search(&mut self, mut saved: HashMap<String, Document>) {
+        let missing: Vec<(String, Option<Document>)> = self
+            .notes
+            .par_iter()
+            .filter(|note| !saved.contains_key(&note.path))
+            .map(|note| {
+                let data = ScribePath::from(&note.path).get_data();
+                let document = data.map(|data| {
+                    Document::from_markdown(&Markdown::parse(&data), note.title.as_deref())
+                });
+                (note.path.clone(), document)
+            })
+            .collect();
+
+        self.search_index = SearchIndex::default();
         for note in &self.notes {
-            if note.has_backlink(path) {
-                links.push(ScribePath::from(&note.path));
+            if let Some(document) = saved.remove(&note.path) {
+                self.search_index.add(&note.path, document);
             }
         }
-        return links;
+        for (path, document) in missing {
+            self.index_document(&path, document);
+        }
     }
 
-    pub fn insert(&mut self, path: &ScribePath) {
-        if self.in_index(path) {
-            self.update(path);
+    fn index_document(&mut self, path: &str, document: Option<Document>) {
+        match document {
+            Some(document) => {
+                if let Some(storage) = &self.storage {
+                    _ = storage.upsert_document(path, &document);
+                }
+                self.search_index.add(path, document);
+            }
+            None => self.search_index.remove(path),
+        }
+    }
+
+    // Runs a query such as `tag:meeting path:inbox/ date:>=2026-10-01 "free text"`
+    pub fn query(&self, query: &str) -> Result<Vec<ScribePath>, QueryError> {
+        Ok(Query::parse(query)?.evaluate(self))
+    }
+
+    // Ranks notes for a quick switcher by title, aliases and relative path, favouring recent edits
+    pub fn fuzzy_find(&self, query: &str, limit: usize) -> Vec<FuzzyMatch> {
+        let query = FuzzyQuery::new(query);
+        if query.is_empty() || limit == 0 {
+            return vec![];
+        }
+
+        let root = format!("{}/", ScribePath::root().as_string(true));
+
+        // Score every note in parallel, keeping which field matched best
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        let mut found: Vec<(i64, usize, usize, FuzzyScore)> = self
+            .notes
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, note)| {
+                let (field, best) = Self::fuzzy_fields(note, &root)
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(field, (_, text))| Some((field, query.score(text)?)))
+                    .min_by_key(|(field, best)| (std::cmp::Reverse(best.score), *field))?;
+                let age = note.stamp.map(|stamp| now.saturating_sub(stamp.mtime));
+                let bonus = age.map_or(0, |age| {
+                    FuzzyMatch::recency_bonus(Duration::from_nanos(age))
+                });
+                Some((best.score + bonus, i, field, best))
+            })
+            .collect();
+
+        let order = |a: &(i64, usize, usize, FuzzyScore), b: &(i64, usize, usize, FuzzyScore)| {
+            b.0.cmp(&a.0)
+                .then_with(|| self.notes[a.1].path.cmp(&self.notes[b.1].path))
+        };
+        if found.len() > limit {
+            found.select_nth_unstable_by(limit, order);
+            found.truncate(limit);
+        }
+        found.sort_by(order);
+
+        found
+            .into_iter()
+            .map(|(score, i, field, best)| {
+                let note = &self.notes[i];
+                let (field, text) = Self::fuzzy_fields(note, &root)[field];
+                FuzzyMatch {
+                    path: ScribePath::from(&note.path),
+                    score,
+                    field,
+                    text: text.to_string(),
+                    positions: best.positions,
+                }
+            })
+            .collect()
+    }
+
+    fn fuzzy_fields<'a>(note: &'a NoteInfo, root: &str) -> Vec<(FuzzyField, &'a str)> {
+        let mut fields: Vec<(FuzzyField, &str)> = vec![];
+        if let Some(title) = &note.title {
+            fields.push((FuzzyField::Title, title));
+        }
+        for alias in note.aliases() {
+            fields.push((FuzzyField::Alias, alias));
+        }
+        let relative = note.path.strip_prefix(root).unwrap_or(&note.path);
+        fields.push((FuzzyField::Path, relative));
+        fields
+    }
+
+    // Ranked full-text matches over note bodies, with a snippet around the best match
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let query = SearchQuery::parse(query);
+        let terms = query.all_terms();
+        self.search_index
+            .search(&query)
+            .into_iter()
+            .take(limit)
+            .map(|(path, score)| {
+                let path = ScribePath::from(&path);
+                let data = path.get_data().unwrap_or_default();
+                let snippet = Snippet::from_text(&data, &terms);
+                SearchResult {
+                    path,
+                    score,
+                    snippet,
+                }
+            })
+            .collect()
+    }
+
+    // Walks each top level folder in parallel, sorted by path so the index order is stable
+    fn note_files(root: &ScribePath) -> Vec<ScribePath> {
+        let mut entries: Vec<String> = match std::fs::read_dir(root.as_pathbuf()) {
+            Ok(dir) => dir
+                .flatten()
+                .map(|entry| entry.path().display().to_string())
+                .collect(),
+            Err(_) => return vec![],
+        };
+        entries.sort();
+
+        entries
+            .par_iter()
+            .flat_map_iter(|entry| ScribePath::from(entry).get_children())
+            .filter(|file| file.is_markdown() & !file.is_template() & !file.is_tmp())
+            .collect()
+    }
+
+    // Loads the saved index and refreshes it, only indexing from scratch without one
+    pub fn load_or_index() -> Self {
+        if ScribeConfig::global().get("storage").map(|s| s.as_str()) == Some("sqlite") {
+            if let Ok(index) = Self::open_database() {
+                return index;
+            }
+        }
+
+        match Self::load(None) {
+            Some(mut index) => {
+                index.refresh();
+                index
+            }
+            None => {
+                let mut index = Self::new();
+                index.index(None);
+                index
+            }
+        }
+    }
+
+    // Opens `.scribe.db`, importing the JSON index into it the first time
+    fn open_database() -> Result<Self, StorageError> {
+        let storage = SqliteStorage::open(Self::get_database().as_pathbuf())?;
+        let json = Self::get_location();
+        if storage.is_empty()? && json.exists() {
+            storage.import_json(json.as_pathbuf())?;
+        }
+
+        let mut index = Self::open(storage)?;
+        if index.notes.is_empty() {
+            index.index(None);
         } else {
-            let note_info = NoteInfo::parse(path);
-            self.notes.insert(self.notes.len(), note_info);
+            index.refresh();
         }
+        Ok(index)
     }
-}
 
-#[cfg(test)]
-mod tests {
+    // Re-parses changed notes, adds new ones and drops deleted ones
+    pub fn refresh(&mut self) -> RefreshSummary {
+        let mut summary = RefreshSummary::default();
+        self.reposition();
 
-    use super::*;
+        // Only files whose metadata changed need to be read
+        let files = Self::note_files(&ScribePath::root());
+        let seen: HashSet<String> = files.iter().map(|file| file.as_string(true)).collect();
+        let mut candidates: Vec<(ScribePath, Option<usize>)> = vec![];
+        for file in files {
+            let position = self.positions.get(&file.as_string(true)).copied();
+            let stamp = position.and_then(|i| self.notes[i].stamp);
+            if stamp.is_some_and(|stamp| stamp.is_current(&file)) {
+                summary.unchanged += 1;
+            } else {
+                candidates.push((file, position));
+            }
+        }
 
-    #[test]
-    fn test_note_info_parser() {
-        // Create temp file
-        let mut new_file = ScribePath::root();
-        new_file.extend("tmp/test.md");
+        let parsers = &self.parsers;
+        let notes = &self.notes;
+        let refreshed: Vec<(ScribePath, Refreshed)> = candidates
+            .into_par_iter()
+            .map(|(file, position)| {
+                let Some(i) = position else {
+                    let (note, document) = Self::parse_note(&file, parsers);
+                    return (file, Refreshed::Added(Box::new(note), document));
+                };
 
-        let test_data = r#"---\ntitle: This is a test file\n tags: ["tag1", "tag2"]\n---\n\n# This is a test file\n\nThis is an embedded link [[projects/test/file]]. While this is a web link: [[www.google.com]]"#;
+                // Touched files with the same content only need a new stamp
+                let data = file.get_data();
+                match data.as_deref().map(|data| FileStamp::new(&file, data)) {
+                    Some(current)
+                        if notes[i]
+                            .stamp
+                            .is_some_and(|stamp| stamp.same_content(&current)) =>
+                    {
+                        (file, Refreshed::Touched(i, current))
+                    }
+                    _ => {
+                        let (note, document) =
+                            Self::parse_document(&file, data.as_deref(), parsers);
+                        (file, Refreshed::Updated(i, Box::new(note), document))
+                    }
+                }
+            })
+            .collect();
 
-        let res = new_file.create_file(test_data);
-        assert!(res.is_ok());
+        for (file, result) in refreshed {
+            match result {
+                Refreshed::Added(note, document) => {
+                    self.positions.insert(note.path.clone(), self.notes.len());
+                    self.notes.push(*note);
+                    self.persist(self.notes.len() - 1);
+                    self.index_document(&file.as_string(true), document);
+                    summary.added.push(file);
+                }
+                Refreshed::Updated(i, note, document) => {
+                    self.notes[i] = *note;
+                    self.persist(i);
+                    self.index_document(&file.as_string(true), document);
+                    summary.updated.push(file);
+                }
+                Refreshed::Touched(i, stamp) => {
+                    self.notes[i].stamp = Some(stamp);
+                    self.persist(i);
+                    summary.unchanged += 1;
+                }
+            }
+        }
 
-        let test_tags: HashSet<String> = HashSet::from(["tag1".to_string(), "tag2".to_string()]);
-        let embedded_links: HashSet<String> = HashSet::from(["projects/test/file".to_string()]);
+        self.notes.retain(|note| {
+            let exists = seen.contains(&note.path);
+            if !exists {
+                summary.removed.push(ScribePath::from(&note.path));
+            }
+            exists
+        });
+        self.reposition();
+        for path in &summary.removed {
+            self.forget(&path.as_string(true));
+            self.search_index.remove(&path.as_string(true));
+        }
+        let changed: Vec<String> = summary
+            .added
+            .iter()
+            .chain(&summary.updated)
+            .chain(&summary.removed)
+            .map(|path| path.as_string(true))
+            .collect();
+        let names = self.files.refresh();
+        self.relink(&changed);
+        self.relink_files(&names);
 
-        let test_note = NoteInfo {
-            path: new_file.as_string(true),
-            title: Some("This is a test file".to_string()),
-            tags: Some(test_tags),
-            date: None,
-            embedded_links: Some(embedded_links),
-            internal_links: None,
-            web_links: Some(HashSet::from(["www.google.com".to_string()])),
+        summary
+    }
+
+    fn get_location() -> ScribePath {
+        let mut root = ScribePath::root();
+        root.extend(".scribe");
+        return root;
+    }
+
+    fn get_database() -> ScribePath {
+        let mut root = ScribePath::root();
+        root.extend(".scribe.db");
+        return root;
+    }
+
+    // Saves every note and search document to JSON, storage with row level changes such as
+    // SQLite already holds each change and has nothing left to write
+    pub fn write(&self) {
+        let json = JsonStorage::new(Self::get_location().as_pathbuf());
+        let storage: &dyn IndexStorage = match &self.storage {
+            Some(storage) => storage.as_ref(),
+            None => &json,
         };
+        _ = storage.flush(&self.notes, self.search_index.documents());
+    }
 
-        let parsed_note = NoteInfo::parse(&new_file);
-        assert_eq!(test_note, parsed_note);
+    fn persist(&self, i: usize) {
+        if let Some(storage) = &self.storage {
+            _ = storage.upsert(&self.notes[i]);
+        }
+    }
 
-        let _res = new_file.delete();
+    fn forget(&self, path: &str) {
+        if let Some(storage) = &self.storage {
+            _ = storage.remove(path);
+        }
     }
 
-    #[test]
-    fn test_note_info_has_backlink() {
-        // Create temp file
-        let mut new_file = ScribePath::root();
-        new_file.extend("tmp/test.md");
+    pub fn delete(&mut self, path: &ScribePath) {
+        if path.is_attachment() {
+            let names = self.files.remove(path);
+            self.relink_files(&names);
+            return;
+        }
+        let path = path.as_string(true);
+        if let Some(i) = self.position(&path) {
+            self.notes.remove(i);
+            self.positions.remove(&path);
+            for position in self.positions.values_mut() {
+                if *position > i {
+                    *position -= 1;
+                }
+            }
+        }
+        self.forget(&path);
+        self.search_index.remove(&path);
+        self.relink(&[path]);
+    }
 
-        let test_data = r#"---\ntitle: This is a test file\n tags: ["tag1", "tag2"]\n---\n\n# This is a test file\n\nThis is an embedded link [[projects/test/file]]. While this is a web link: [[www.google.com]]"#;
+    fn in_index(&self, path: &ScribePath) -> bool {
+        self.position(&path.as_string(true)).is_some()
+    }
 
-        let res = new_file.create_file(test_data);
-        assert!(res.is_ok());
+    pub fn update(&mut self, path: &ScribePath) {
+        let absolute = path.as_string(true);
+        if let Some(i) = self.position(&absolute) {
+            let (new_note, document) = Self::parse_note(path, &self.parsers);
+            self.notes[i] = new_note;
+            self.persist(i);
+            self.index_document(&absolute, document);
+        }
+        self.relink(&[absolute]);
+    }
 
-        let parsed_note = NoteInfo::parse(&new_file);
+    // Applies to notes parsed from now on, re-index to apply it to existing notes
+    pub fn register_parser<P: NoteParser + 'static>(&mut self, parser: P) {
+        self.parsers.register(Box::new(parser));
+    }
 
-        let mut backlinked_file = ScribePath::root();
-        backlinked_file.extend("projects/test/file.md");
-        assert!(parsed_note.has_backlink(&backlinked_file));
+    pub fn find_extra(&self, name: &str) -> Vec<(ScribePath, &serde_json::Value)> {
+        let mut found: Vec<(ScribePath, &serde_json::Value)> = vec![];
+        for note in &self.notes {
+            if let Some(value) = note.extra.get(name) {
+                found.push((ScribePath::from(&note.path), value));
+            }
+        }
+        found
+    }
 
-        let mut not_backlinked_file = ScribePath::root();
-        not_backlinked_file.extend("tmp/test.md");
-        assert!(!parsed_note.has_backlink(&not_backlinked_file));
+    // The resolved links between notes, for orphans, hubs, components and PageRank
+    pub fn link_graph(&self) -> &LinkGraph {
+        &self.graph
+    }
 
-        let _res = new_file.delete();
+    // The link graph as DOT for Graphviz, GraphML for Gephi or nodes and links JSON for D3
+    pub fn export_graph(&self, format: ExportFormat, options: &ExportOptions) -> String {
+        GraphExport::build(self, options).write(format)
     }
 
-    #[test]
-    fn test_index_load_vs_index() {
-        let mut index_path = ScribePath::root();
-        index_path.extend("test_index.json");
-        let loaded_index = ScribeIndex::load(Some(index_path));
+    // Kept current on every change, so this is a lookup rather than a scan of every note
+    pub fn get_backlinks(&self, path: &ScribePath) -> Vec<ScribePath> {
+        self.graph.incoming(path)
+    }
 
-        assert!(loaded_index.is_some());
+    // Notes within `hops` links of `path`, for a local graph view
+    pub fn get_neighbourhood(&self, path: &ScribePath, hops: usize) -> Vec<(ScribePath, usize)> {
+        self.graph.neighbourhood(path, hops)
+    }
 
-        // Test Creating a New Index
-        let mut index = ScribeIndex::new();
-        index.index(None);
+    pub fn get_attachments(&self, path: &ScribePath) -> Vec<ScribePath> {
+        let mut attachments: Vec<ScribePath> = vec![];
+        for note in self
+            .notes
+            .iter()
+            .filter(|note| note.path == path.as_string(true))
+        {
+            for attachment in note.attachments.iter().flatten() {
+                if let Some(file) = self.files.resolve(&note.path, &attachment.target) {
+                    if !attachments.contains(file) {
+                        attachments.push(file.clone());
+                    }
+                }
+            }
+        }
+        attachments
+    }
 
-        let unwrapped = loaded_index.unwrap();
+    // Files no note embeds or links to
+    pub fn unreferenced_attachments(&self) -> Vec<ScribePath> {
+        let mut referenced: HashSet<String> = HashSet::new();
+        for note in &self.notes {
+            for attachment in note.attachments.iter().flatten() {
+                if let Some(file) = self.files.resolve(&note.path, &attachment.target) {
+                    referenced.insert(file.as_string(true));
+                }
+            }
+            for link in note.resolved_links.iter().flatten() {
+                if let Resolution::File(path) = &link.resolution {
+                    referenced.insert(path.clone());
+                }
+            }
+        }
+        self.files
+            .files()
+            .filter(|file| !referenced.contains(&file.as_string(true)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn missing_attachments(&self) -> Vec<(ScribePath, &Attachment)> {
+        let mut missing: Vec<(ScribePath, &Attachment)> = vec![];
+        for note in &self.notes {
+            for attachment in note.attachments.iter().flatten() {
+                if self.files.resolve(&note.path, &attachment.target).is_none() {
+                    missing.push((ScribePath::from(&note.path), attachment));
+                }
+            }
+        }
+        missing
+    }
+
+    fn find_tasks<F: Fn(&Task) -> bool>(&self, predicate: F) -> Vec<(ScribePath, &Task)> {
+        let mut tasks: Vec<(ScribePath, &Task)> = vec![];
+        for note in &self.notes {
+            for task in note.tasks.iter().flatten().filter(|task| predicate(task)) {
+                tasks.push((ScribePath::from(&note.path), task));
+            }
+        }
+        tasks
+    }
+
+    pub fn open_tasks(&self) -> Vec<(ScribePath, &Task)> {
+        self.find_tasks(|task| !task.completed)
+    }
+
+    pub fn overdue_tasks(&self, today: NaiveDate) -> Vec<(ScribePath, &Task)> {
+        self.find_tasks(|task| task.is_overdue(today))
+    }
+
+    pub fn tasks_by_tag(&self, tag: &str) -> Vec<(ScribePath, &Task)> {
+        let tag = Parser::normalize(tag);
+        self.find_tasks(|task| task.tags.contains(&tag))
+    }
+
+    pub fn find_callouts(&self, kind: &str) -> Vec<(ScribePath, &Callout)> {
+        let mut callouts: Vec<(ScribePath, &Callout)> = vec![];
+        for note in &self.notes {
+            for callout in note.callouts.iter().flatten() {
+                if callout.kind.eq_ignore_ascii_case(kind) {
+                    callouts.push((ScribePath::from(&note.path), callout));
+                }
+            }
+        }
+        callouts
+    }
+
+    pub fn undefined_footnotes(&self) -> Vec<(ScribePath, &Footnote)> {
+        let mut footnotes: Vec<(ScribePath, &Footnote)> = vec![];
+        for note in &self.notes {
+            for footnote in note.footnotes.iter().flatten() {
+                if !footnote.is_defined() {
+                    footnotes.push((ScribePath::from(&note.path), footnote));
+                }
+            }
+        }
+        footnotes
+    }
+
+    pub fn find_tag_occurrences(&self, tag: &str) -> Vec<(ScribePath, Span)> {
+        let tag = Parser::normalize(tag);
+        let mut occurrences: Vec<(ScribePath, Span)> = vec![];
+        for note in &self.notes {
+            if let Some(spans) = &note.spans {
+                for located in spans.tags.iter().filter(|located| located.value == tag) {
+                    occurrences.push((ScribePath::from(&note.path), located.span));
+                }
+            }
+        }
+        occurrences
+    }
+
+    pub fn tag_tree(&self) -> Vec<TagNode> {
+        let mut notes: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+        for (i, note) in self.notes.iter().enumerate() {
+            for tag in note.tags.iter().flatten() {
+                for ancestor in Tags::ancestors(tag) {
+                    notes.entry(ancestor.to_string()).or_default().insert(i);
+                }
+            }
+        }
+        TagNode::build("", &notes)
+    }
+
+    pub fn find_by_tag_prefix(&self, prefix: &str) -> Vec<ScribePath> {
+        let mut paths: Vec<ScribePath> = vec![];
+        for note in &self.notes {
+            if note
+                .tags
+                .iter()
+                .flatten()
+                .any(|tag| Tags::is_under(tag, prefix))
+            {
+                paths.push(ScribePath::from(&note.path));
+            }
+        }
+        paths
+    }
+
+    // Renames `from` and every tag nested beneath it, returning the rewritten notes
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> std::io::Result<Vec<ScribePath>> {
+        let from = Parser::normalize(from.trim_start_matches('#').trim_end_matches('/'));
+        let to = Parser::normalize(to.trim_start_matches('#').trim_end_matches('/'));
+
+        let paths = self.find_by_tag_prefix(&from);
+        for path in &paths {
+            let Some(data) = path.get_data() else {
+                continue;
+            };
+
+            // Replace from the end so earlier spans stay valid
+            let mut updated = data.clone();
+            let mut located = Tags::locate(&Markdown::parse(&data));
+            located.sort_by_key(|tag| std::cmp::Reverse(tag.span.start));
+            for tag in located {
+                let range = tag.span.range();
+                let written = Parser::normalize(&data[range.clone()]);
+                if Tags::is_under(&tag.value, &from) && written == tag.value {
+                    let renamed = format!("{}{}", to, &tag.value[from.len()..]);
+                    updated.replace_range(range, &renamed);
+                }
+            }
+
+            std::fs::write(path.as_string(true), updated)?;
+            self.update(path);
+        }
+        Ok(paths)
+    }
+
+    pub fn find_by_field(&self, key: &str, value: &str) -> Vec<ScribePath> {
+        let mut paths: Vec<ScribePath> = vec![];
+        for note in &self.notes {
+            if note
+                .get_field(key)
+                .is_some_and(|field| field.matches(value))
+            {
+                paths.push(ScribePath::from(&note.path));
+            }
+        }
+        paths
+    }
+
+    pub fn find_with_field(&self, key: &str) -> Vec<ScribePath> {
+        let mut paths: Vec<ScribePath> = vec![];
+        for note in &self.notes {
+            if note.get_field(key).is_some() {
+                paths.push(ScribePath::from(&note.path));
+            }
+        }
+        paths
+    }
+
+    // Notes are parsed, other files are only listed for links and attachments to resolve to
+    pub fn insert(&mut self, path: &ScribePath) {
+        if path.is_attachment() {
+            let names = self.files.insert(path);
+            self.relink_files(&names);
+        } else if self.in_index(path) {
+            self.update(path);
+        } else {
+            let absolute = path.as_string(true);
+            let (note_info, document) = Self::parse_note(path, &self.parsers);
+            self.positions.insert(absolute.clone(), self.notes.len());
+            self.notes.push(note_info);
+            self.persist(self.notes.len() - 1);
+            self.index_document(&absolute, document);
+            self.relink(&[absolute]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::TimeZone;
+
+    // A folder of its own under the vault's `tmp`, removed with everything in it when dropped
+    struct TestDir {
+        root: ScribePath,
+    }
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let mut root = ScribePath::root();
+            root.extend(&format!("tmp/{}", name));
+            let _ = std::fs::remove_dir_all(root.as_pathbuf());
+            std::fs::create_dir_all(root.as_pathbuf()).unwrap();
+            Self { root }
+        }
+
+        fn path(&self, relative: &str) -> ScribePath {
+            let mut path = self.root.clone();
+            path.extend(relative);
+            path
+        }
+
+        fn create(&self, relative: &str, data: &str) -> ScribePath {
+            let path = self.path(relative);
+            assert!(path.create_file(data).is_ok());
+            path
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(self.root.as_pathbuf());
+        }
+    }
+
+    #[test]
+    fn test_note_info_parser() {
+        let dir = TestDir::new("note_info_parser");
+        // Create temp file
+        let new_file = dir.path("test.md");
+
+        let test_data = r#"---\ntitle: This is a test file\n tags: ["tag1", "tag2"]\n---\n\n# This is a test file\n\nThis is an embedded link [[projects/test/file]]. While this is a web link: [[www.google.com]]"#;
+
+        let res = new_file.create_file(test_data);
+        assert!(res.is_ok());
+
+        let test_tags: HashSet<String> = HashSet::from(["tag1".to_string(), "tag2".to_string()]);
+        let embedded_links: HashSet<WikiLink> =
+            HashSet::from([WikiLink::from_inner("projects/test/file", false)]);
+
+        let test_note = NoteInfo {
+            path: new_file.as_string(true),
+            // The data is a single line, so neither `title:` nor the heading starts a line
+            title: None,
+            tags: Some(test_tags),
+            date: None,
+            embedded_links: Some(embedded_links),
+            internal_links: None,
+            web_links: Some(HashSet::from(["www.google.com".to_string()])),
+            frontmatter: None,
+            spans: None,
+            headings: None,
+            tasks: None,
+            inline_fields: None,
+            attachments: None,
+            callouts: None,
+            footnotes: None,
+            block_ids: None,
+            created: None,
+            modified: None,
+            extra: BTreeMap::new(),
+            stamp: None,
+            resolved_links: None,
+        };
+
+        let mut parsed_note = NoteInfo::parse(&new_file);
+        let spans = parsed_note.spans.take().unwrap();
+        let stamp = parsed_note.stamp.take().unwrap();
+        assert_eq!(test_note, parsed_note);
+        assert_eq!(stamp.size, test_data.len() as u64);
+
+        assert_eq!(spans.tags.len(), 2);
+        assert_eq!(spans.embedded_links[0].value.target, "projects/test/file");
+        assert_eq!(spans.embedded_links[0].span.line, 1);
+        assert_eq!(
+            &test_data[spans.embedded_links[0].span.range()],
+            "[[projects/test/file]]"
+        );
+    }
+
+    #[test]
+    fn test_note_info_headings() {
+        let dir = TestDir::new("note_info_headings");
+        let new_file = dir.path("test_headings.md");
+
+        let test_data = "# Outline\n\n## First Part\n\n### Details\n\n## Second Part";
+        let res = new_file.create_file(test_data);
+        assert!(res.is_ok());
+
+        let parsed_note = NoteInfo::parse(&new_file);
+        let headings = parsed_note.headings.as_ref().unwrap();
+        let levels: Vec<u8> = headings.iter().map(|heading| heading.level).collect();
+        assert_eq!(levels, vec![1, 2, 3, 2]);
+
+        assert_eq!(parsed_note.get_heading("Second Part").unwrap().span.line, 7);
+        assert_eq!(
+            parsed_note.get_heading("first-part").unwrap().text,
+            "First Part"
+        );
+        assert!(parsed_note.get_heading("Missing").is_none());
+    }
+
+    #[test]
+    fn test_note_info_has_backlink() {
+        let dir = TestDir::new("note_info_has_backlink");
+        // Create temp file
+        let new_file = dir.path("test.md");
+
+        let test_data = r#"---\ntitle: This is a test file\n tags: ["tag1", "tag2"]\n---\n\n# This is a test file\n\nThis is an embedded link [[projects/test/file]]. While this is a web link: [[www.google.com]]"#;
+
+        let res = new_file.create_file(test_data);
+        assert!(res.is_ok());
+
+        let parsed_note = NoteInfo::parse(&new_file);
+
+        let mut backlinked_file = ScribePath::root();
+        backlinked_file.extend("projects/test/file.md");
+        assert!(parsed_note.has_backlink(&backlinked_file));
+
+        let not_backlinked_file = dir.path("test.md");
+        assert!(!parsed_note.has_backlink(&not_backlinked_file));
+    }
+
+    #[test]
+    fn test_note_info_has_backlink_anchors() {
+        let dir = TestDir::new("note_info_has_backlink_anchors");
+        let new_file = dir.path("test_anchor_links.md");
+
+        let test_data = "See [[projects/test/file#Section|the section]]\n\n![[projects/test/other.md^block-id]]";
+        let res = new_file.create_file(test_data);
+        assert!(res.is_ok());
+
+        let parsed_note = NoteInfo::parse(&new_file);
+
+        let mut linked_file = ScribePath::root();
+        linked_file.extend("projects/test/file.md");
+        assert!(parsed_note.has_backlink(&linked_file));
+
+        let mut embedded_file = ScribePath::root();
+        embedded_file.extend("projects/test/other.md");
+        assert!(parsed_note.has_backlink(&embedded_file));
+
+        let embeds: Vec<&WikiLink> = parsed_note.embeds().collect();
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0].block_id, Some("block-id".to_string()));
+    }
+
+    #[test]
+    fn test_index_tasks() {
+        let dir = TestDir::new("index_tasks");
+        let first_file = dir.create(
+            "test_tasks_1.md",
+            "- [ ] Ship release #work due:2026-10-01\n- [x] Plan #work",
+        );
+
+        let second_file = dir.create("test_tasks_2.md", "- [ ] Water plants 📅 2026-11-01");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&first_file);
+        index.insert(&second_file);
+
+        assert_eq!(index.open_tasks().len(), 2);
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let overdue = index.overdue_tasks(today);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].0, first_file);
+        assert_eq!(overdue[0].1.text, "Ship release #work due:2026-10-01");
+
+        assert_eq!(index.tasks_by_tag("work").len(), 2);
+        assert!(index.tasks_by_tag("home").is_empty());
+    }
+
+    #[test]
+    fn test_index_relink() {
+        let dir = TestDir::new("relink");
+        let source = dir.create(
+            "inbox/source.md",
+            "See [[plan]], [[roadmap]] and [x](../projects/x.md)",
+        );
+        let plan = dir.create("projects/plan.md", "# Plan");
+        let x = dir.create("projects/x.md", "# X");
+        let other = dir.create("other.md", "# Other\n\n[[x]]");
+        let old_plan = dir.create("archive/plan.md", "# Old plan");
+
+        // Each change must leave the links as resolving the whole vault again would
+        let check = |index: &ScribeIndex| {
+            let mut fresh = ScribeIndex::new();
+            fresh.notes = index.notes.clone();
+            fresh.link_all();
+            assert_eq!(fresh.notes, index.notes);
+            assert_eq!(fresh.graph, index.graph);
+        };
+        let mut index = ScribeIndex::new();
+        for path in [&source, &plan, &x, &other] {
+            index.insert(path);
+            check(&index);
+        }
+        assert_eq!(
+            index
+                .resolver
+                .linking(&BTreeSet::from(["plan".to_string()])),
+            BTreeSet::from([source.as_string(true)])
+        );
+
+        index.insert(&old_plan);
+        check(&index);
+        assert_eq!(index.ambiguous_links().len(), 1);
+
+        let _ = plan.create_file("---\naliases: [Roadmap]\n---\n# Plan");
+        index.update(&plan);
+        check(&index);
+        assert_eq!(index.get_backlinks(&plan), vec![source.clone()]);
+
+        index.delete(&old_plan);
+        check(&index);
+        assert!(index.ambiguous_links().is_empty());
+
+        index.delete(&x);
+        check(&index);
+        assert_eq!(index.broken_links().len(), 2);
+    }
+
+    #[test]
+    fn test_index_resolve_links() {
+        let dir = TestDir::new("resolve");
+        let file = dir.create("projects/test/file.md", "# File");
+
+        let plan = dir.create("projects/plan.md", "---\naliases: [Roadmap]\n---\n# Plan");
+
+        let other_plan = dir.create("archive/plan.md", "# Old plan");
+
+        let source = dir.create(
+            "inbox/source.md",
+            "See [[file]], [the file](../projects/test/file.md), [[roadmap]] and [[plan]].",
+        );
+
+        let mut index = ScribeIndex::new();
+        for path in [&file, &plan, &other_plan, &source] {
+            index.insert(path);
+        }
+
+        assert_eq!(index.get_backlinks(&file), vec![source.clone()]);
+        assert_eq!(index.get_backlinks(&plan), vec![source.clone()]);
+        assert_eq!(
+            index.resolve_link(&source, "File.md"),
+            Resolution::Note(file.as_string(true))
+        );
+
+        let ambiguous = index.ambiguous_links();
+        assert_eq!(ambiguous.len(), 1);
+        assert_eq!(ambiguous[0].1.target, "plan");
+        assert_eq!(ambiguous[0].1.span.column, 69);
+
+        assert_eq!(
+            index.get_neighbourhood(&file, 2),
+            vec![(file.clone(), 0), (source.clone(), 1), (plan.clone(), 2)]
+        );
+
+        // Deleting one of the two plans resolves the bare name to the other
+        index.delete(&other_plan);
+        assert!(index.ambiguous_links().is_empty());
+        assert_eq!(index.link_graph(), &LinkGraph::from_notes(&index.notes));
+        assert_eq!(index.get_backlinks(&plan), vec![source.clone()]);
+        assert_eq!(
+            index.link_graph().outgoing(&source),
+            vec![plan.clone(), file.clone()]
+        );
+        let note = index
+            .notes
+            .iter()
+            .find(|note| note.path == source.as_string(true))
+            .unwrap();
+        let links = note.resolved_links.as_ref().unwrap();
+        assert_eq!(links.len(), 4);
+        assert_eq!(links[1].kind, LinkKind::Markdown);
+        assert_eq!(
+            links[3].resolution.note(),
+            Some(plan.as_string(true).as_str())
+        );
+    }
+
+    #[test]
+    fn test_index_broken_links() {
+        let dir = TestDir::new("broken");
+        let target = dir.create("target.md", "# Intro\n\nA paragraph ^kept\n");
+
+        let source = dir.create("source.md", "[[target#Intro]] [[target#Missing]] [[target^kept]] [[target#^gone]]\n\n[[nowhere]] [x](target.md#intro) [y](target.md#absent) [z](../nothing.md)\n\n# Local\n\n[[report.docx]] [r](../../assets/report.docx) [[gone.pdf]] [a](#Local) [b](#nope)");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&target);
+        index.insert(&source);
+
+        let broken = index.broken_links();
+        let problems: Vec<(&str, LinkProblem)> = broken
+            .iter()
+            .map(|broken| (broken.link.target.as_str(), broken.problem))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("target", LinkProblem::MissingHeading),
+                ("target", LinkProblem::MissingBlock),
+                ("nowhere", LinkProblem::MissingNote),
+                ("target.md", LinkProblem::MissingHeading),
+                ("../nothing.md", LinkProblem::MissingNote),
+                ("gone.pdf", LinkProblem::MissingNote),
+                ("", LinkProblem::MissingHeading),
+            ]
+        );
+        assert!(broken.iter().all(|broken| broken.source == source));
+        assert_eq!(
+            broken[2].to_string(),
+            "tmp/broken/source.md:3:1: no note matches `nowhere`"
+        );
+        assert_eq!(
+            broken[1].to_string(),
+            "tmp/broken/source.md:1:53: no block `^gone` in `target`"
+        );
+        assert_eq!(
+            broken[5].to_string(),
+            "tmp/broken/source.md:7:47: no note or file matches `gone.pdf`"
+        );
+        assert_eq!(
+            broken[6].to_string(),
+            "tmp/broken/source.md:7:72: no heading `nope` in this note"
+        );
+    }
+
+    #[test]
+    fn test_index_link_graph() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+        let graph = index.link_graph();
+
+        let first = ScribePath::from("inbox/test_file1.md");
+        assert_eq!(graph.hubs(1), vec![(first.clone(), 2)]);
+        assert_eq!(graph.hubs(1)[0].1, index.get_backlinks(&first).len());
+        assert!(graph
+            .orphans()
+            .contains(&ScribePath::from("inbox/test_file3.md")));
+        assert_eq!(
+            graph.components()[0],
+            vec![
+                first,
+                ScribePath::from("inbox/test_file2.md"),
+                ScribePath::from("inbox/test_file4.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_export_graph() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        let options = ExportOptions {
+            folders: vec!["inbox".to_string()],
+            color_by_category: true,
+            include_tags: true,
+            ..Default::default()
+        };
+        let export = GraphExport::build(&index, &options);
+        let ids: Vec<&str> = export.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "inbox/test_file1.md",
+                "inbox/test_file2.md",
+                "inbox/test_file3.md",
+                "inbox/test_file4.md",
+                "#tag1",
+                "#tag2",
+                "#tag3",
+                "#test_tag",
+                "#test_tag2",
+            ]
+        );
+        assert_eq!(export.nodes[0].color, export.nodes[3].color);
+        assert!(export.links.iter().any(
+            |link| link.source == "inbox/test_file2.md" && link.target == "inbox/test_file1.md"
+        ));
+
+        // Tag filters apply to nested tags, tag nodes are only added when asked for
+        let options = ExportOptions {
+            tags: vec!["tag1".to_string()],
+            ..Default::default()
+        };
+        let dot = index.export_graph(ExportFormat::Dot, &options);
+        assert!(dot.contains("\"inbox/test_file1.md\" [label=\"Test File 1\"];"));
+        assert!(!dot.contains("#tag1"));
+        assert!(!dot.contains("test_file3"));
+    }
+
+    #[test]
+    fn test_index_unicode_backlinks() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        // The link in the Japanese note is written in decomposed (NFD) form
+        let german = ScribePath::from("international/größe.md");
+        let japanese = ScribePath::from("international/会議メモ.md");
+        assert_eq!(index.get_backlinks(&german), vec![japanese.clone()]);
+        assert_eq!(index.get_backlinks(&japanese), vec![german]);
+
+        let note = index
+            .notes
+            .iter()
+            .find(|note| note.path == japanese.as_string(true))
+            .unwrap();
+        assert_eq!(note.title, Some("会議メモ — 第3四半期".to_string()));
+        assert!(note.get_heading("議題").is_some());
+    }
+
+    #[test]
+    fn test_index_attachments() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        let note = ScribePath::from("reference/attachments.md");
+        let diagram = ScribePath::from("assets/diagram.svg");
+        let report = ScribePath::from("assets/report.docx");
+        assert_eq!(index.get_attachments(&note), vec![diagram, report]);
+        // Any file besides a note counts, the fixture index included
+        assert_eq!(
+            index.unreferenced_attachments(),
+            vec![
+                ScribePath::from("assets/unused.svg"),
+                ScribePath::from("test_index.json")
+            ]
+        );
+
+        let missing = index.missing_attachments();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].0, note);
+        assert_eq!(missing[0].1.target, "assets/missing.png");
+        assert_eq!(missing[0].1.span.line, 7);
+    }
+
+    #[test]
+    fn test_index_attachment_links() {
+        let dir = TestDir::new("files");
+        let note = dir.create(
+            "links.md",
+            "[Unused](../../assets/unused.svg) and [[data.csv]]",
+        );
+
+        let mut index = ScribeIndex::new();
+        index.insert(&note);
+        let unused = ScribePath::from("assets/unused.svg");
+        assert!(!index.unreferenced_attachments().contains(&unused));
+        let broken: Vec<String> = index.broken_links().iter().map(|b| b.to_string()).collect();
+        assert_eq!(
+            broken,
+            vec!["tmp/files/links.md:1:39: no note or file matches `data.csv`"]
+        );
+
+        // Files added and removed later re-resolve the links to them
+        let data = dir.create("data.csv", "a,b");
+        index.insert(&data);
+        assert!(index.broken_links().is_empty());
+        assert!(!index.unreferenced_attachments().contains(&data));
+
+        let _res = data.delete();
+        index.delete(&data);
+        assert_eq!(index.broken_links().len(), 1);
+    }
+
+    #[test]
+    fn test_index_callouts_and_footnotes() {
+        let dir = TestDir::new("index_callouts_and_footnotes");
+        let new_file = dir.create("test_callouts_footnotes.md", "> [!question] Who reviews?\n> Still open\n\n> [!warning]\n> Careful\n\nA claim[^1] and a gap[^2].\n\n[^1]: Source");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&new_file);
+
+        let questions = index.find_callouts("question");
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].0, new_file);
+        assert_eq!(questions[0].1.title, Some("Who reviews?".to_string()));
+        assert_eq!(questions[0].1.body, "Still open");
+        assert_eq!(index.find_callouts("WARNING").len(), 1);
+
+        let undefined = index.undefined_footnotes();
+        assert_eq!(undefined.len(), 1);
+        assert_eq!(undefined[0].1.label, "2");
+        assert_eq!(undefined[0].1.references[0].line, 7);
+    }
+
+    struct MentionParser {}
+
+    impl NoteParser for MentionParser {
+        fn name(&self) -> &str {
+            "mentions"
+        }
+
+        fn parse(&self, markdown: &Markdown) -> Option<serde_json::Value> {
+            let mut mentions: Vec<String> = vec![];
+            for (_, text) in markdown.prose() {
+                for word in text.split_whitespace() {
+                    if let Some(name) = word.strip_prefix('@') {
+                        mentions.push(name.to_string());
+                    }
+                }
+            }
+            Some(serde_json::json!(mentions)).filter(|_| !mentions.is_empty())
+        }
+    }
+
+    #[test]
+    fn test_index_registered_parser() {
+        let dir = TestDir::new("index_registered_parser");
+        let new_file = dir.create(
+            "test_registered_parser.md",
+            "Sync with @alice and @bob\n\n`@code`",
+        );
+
+        let mut index = ScribeIndex::new();
+        index.register_parser(MentionParser {});
+        index.insert(&new_file);
+
+        let note = &index.notes[0];
+        assert_eq!(
+            note.extra.get("mentions"),
+            Some(&serde_json::json!(["alice", "bob"]))
+        );
+        assert_eq!(
+            index.find_by_field("mentions", "bob"),
+            vec![new_file.clone()]
+        );
+        assert_eq!(index.find_with_field("mentions").len(), 1);
+        assert_eq!(index.find_extra("mentions").len(), 1);
+
+        // Registered parsers are not persisted with the index
+        let json = serde_json::to_string(&index).unwrap();
+        let loaded: ScribeIndex = serde_json::from_str(&json).unwrap();
+        assert!(loaded.parsers.is_empty());
+        assert_eq!(loaded.notes, index.notes);
+    }
+
+    #[test]
+    fn test_index_order_is_stable() {
+        let mut first = ScribeIndex::new();
+        first.index(None);
+        let mut second = ScribeIndex::new();
+        second.index(None);
+
+        let paths = |index: &ScribeIndex| -> Vec<String> {
+            index.notes.iter().map(|note| note.path.clone()).collect()
+        };
+        assert_eq!(paths(&first), paths(&second));
+
+        let inbox: Vec<String> = paths(&first)
+            .into_iter()
+            .filter(|path| path.contains("/inbox/"))
+            .collect();
+        let mut sorted = inbox.clone();
+        sorted.sort();
+        assert_eq!(inbox, sorted);
+        assert_eq!(inbox.len(), 4);
+    }
+
+    #[test]
+    fn test_index_sqlite_storage() {
+        let dir = TestDir::new("index_sqlite_storage");
+        let note = dir.create(
+            "test_sqlite_note.md",
+            "---\ntitle: Stored\nstatus: draft\n---\n\n#stored [[test_file1]]",
+        );
+
+        let mut index = ScribeIndex::open(SqliteStorage::open_in_memory().unwrap()).unwrap();
+        assert!(index.notes.is_empty());
+        let stored = |index: &ScribeIndex| index.storage.as_ref().unwrap().load().unwrap();
+
+        // Row level changes reach the database without a write
+        index.insert(&note);
+        assert_eq!(stored(&index), index.notes);
+        assert_eq!(stored(&index)[0].title, Some("Stored".to_string()));
+
+        let _ = note.create_file("---\ntitle: Renamed\n---\n");
+        index.update(&note);
+        assert_eq!(stored(&index)[0].title, Some("Renamed".to_string()));
+
+        index.delete(&note);
+        assert!(stored(&index).is_empty());
+    }
+
+    #[test]
+    fn test_index_search() {
+        let dir = TestDir::new("index_search");
+        let first = dir.create(
+            "test_search_first.md",
+            "# Garden\n\nPlanning the vegetable garden, the gardening budget and seeds.",
+        );
+        let second = dir.create(
+            "test_search_second.md",
+            "# Budget\n\nQuarterly budget review, the budget is tight.",
+        );
+
+        let mut index = ScribeIndex::new();
+        index.insert(&first);
+        index.insert(&second);
+
+        let results = index.search("budget", 10);
+        let paths: Vec<&ScribePath> = results.iter().map(|result| &result.path).collect();
+        assert_eq!(paths, vec![&second, &first]);
+        assert!(results[1]
+            .snippet
+            .highlight("[", "]")
+            .contains("gardening [budget] and seeds"));
+        assert_eq!(index.search("budget", 1).len(), 1);
+        assert_eq!(index.search("\"budget review\"", 10)[0].path, second);
+
+        // Updates and deletes keep the search index in step
+        let _ = first.create_file("Nothing about money");
+        index.update(&first);
+        assert_eq!(index.search("garden", 10).len(), 0);
+        index.delete(&second);
+        assert!(index.search("budget", 10).is_empty());
+    }
+
+    #[test]
+    fn test_index_saved_search() {
+        let dir = TestDir::new("index_saved_search");
+        let note = dir.create(
+            "test_saved_search.md",
+            "# Garden\n\nPlanning the vegetable garden",
+        );
+        let location = dir.path("test_saved_search.json");
+        let database = dir.path("test_saved_search.db");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&note);
+        index.storage = Some(Box::new(JsonStorage::new(location.as_pathbuf())));
+        index.write();
+
+        let mut index =
+            ScribeIndex::open(SqliteStorage::open(database.as_pathbuf()).unwrap()).unwrap();
+        index.insert(&note);
+
+        // Reopened indexes search what was saved rather than re-reading the note
+        let _ = note.create_file("Nothing about plants");
+        let reopened = [
+            ScribeIndex::open(JsonStorage::new(location.as_pathbuf())).unwrap(),
+            ScribeIndex::open(SqliteStorage::open(database.as_pathbuf()).unwrap()).unwrap(),
+        ];
+        for index in reopened {
+            assert_eq!(index.search("garden", 10).len(), 1);
+            assert!(index.search("plants", 10).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_index_query() {
+        let dir = TestDir::new("query");
+        let notes = [
+            ("inbox/standup.md", "---\ndate: 2026-10-12\nstatus: done\n---\n# Standup\n\n#meeting/daily about the roadmap, see [[tmp/query/projects/scribe]]"),
+            ("inbox/review.md", "---\ndate: 2026-10-02\n---\n# Review\n\n#meeting budget review with the roadmap roadmap roadmap"),
+            ("inbox/old.md", "---\ndate: 2026-09-01\n---\n# Old\n\n#meeting [[tmp/query/projects/scribe]]"),
+            ("projects/scribe.md", "# Scribe\n\n#project"),
+        ];
+        let mut index = ScribeIndex::new();
+        let mut paths: Vec<ScribePath> = vec![];
+        for (relative, data) in notes {
+            let path = dir.create(relative, data);
+            index.insert(&path);
+            paths.push(path);
+        }
+        let [standup, review, old, scribe] = [0, 1, 2, 3].map(|i| paths[i].clone());
+
+        let found = index
+            .query("tag:meeting path:tmp/query/inbox/ date:>=2026-10-01 links-to:tmp/query/projects/scribe")
+            .unwrap();
+        assert_eq!(found, vec![standup.clone()]);
+
+        assert_eq!(
+            index.query("tag:meeting sort:-date").unwrap(),
+            vec![standup.clone(), review.clone(), old.clone()]
+        );
+        assert_eq!(index.query("-tag:meeting").unwrap(), vec![scribe.clone()]);
+        assert_eq!(
+            index.query("field:status=done").unwrap(),
+            vec![standup.clone()]
+        );
+
+        // Free text ranks by relevance unless a sort is given
+        assert_eq!(
+            index.query("roadmap").unwrap(),
+            vec![review.clone(), standup.clone()]
+        );
+        assert_eq!(
+            index.query("roadmap sort:title limit:1").unwrap(),
+            vec![review]
+        );
+        assert!(index.query("tag:meeting date:>=nope").is_err());
+    }
+
+    #[test]
+    fn test_index_query_paths_and_links() {
+        let dir = TestDir::new("query_links");
+        let notes = [
+            ("inbox/a.md", "# A\n\nSee [[target]] for the plan"),
+            ("inbox2/b.md", "# B\n\nSee [Target](../projects/target.md)"),
+            ("projects/target.md", "# Target"),
+        ];
+        let mut index = ScribeIndex::new();
+        let mut paths: Vec<ScribePath> = vec![];
+        for (relative, data) in notes {
+            let path = dir.create(relative, data);
+            index.insert(&path);
+            paths.push(path);
+        }
+        let [a, b, target] = [0, 1, 2].map(|i| paths[i].clone());
+
+        // Paths match whole folders
+        assert_eq!(
+            index.query("path:tmp/query_links/inbox").unwrap(),
+            vec![a.clone()]
+        );
+        assert_eq!(
+            index.query("path:/tmp/query_links/inbox/").unwrap(),
+            vec![a.clone()]
+        );
+        assert_eq!(
+            index.query("path:tmp/query_links/projects/target").unwrap(),
+            vec![target.clone()]
+        );
+
+        // Link targets resolve like a wiki link
+        assert_eq!(
+            index.query("links-to:target").unwrap(),
+            vec![a.clone(), b.clone()]
+        );
+        assert_eq!(
+            index
+                .query("links-to:query_links/projects/target.md")
+                .unwrap(),
+            vec![a.clone(), b.clone()]
+        );
+        assert!(index.query("links-to:nowhere").unwrap().is_empty());
+
+        // Stopwords alone leave the text filter out
+        assert_eq!(
+            index.query("path:tmp/query_links the and").unwrap(),
+            vec![a, b, target]
+        );
+    }
+
+    #[test]
+    fn test_index_fuzzy_find() {
+        let dir = TestDir::new("fuzzy");
+        let notes = [
+            (
+                "weekly_meeting.md",
+                "---\ntitle: Weekly Meeting\naliases: [standup, sync]\n---\n",
+            ),
+            (
+                "meeting_archive.md",
+                "---\ntitle: Old Meetings Archive\n---\n",
+            ),
+            (
+                "misc.md",
+                "---\ntitle: Miscellaneous Entries Extracted Today\n---\n",
+            ),
+        ];
+        let mut index = ScribeIndex::new();
+        let mut paths: Vec<ScribePath> = vec![];
+        for (relative, data) in notes {
+            let path = dir.create(relative, data);
+            index.insert(&path);
+            paths.push(path);
+        }
+
+        let found = index.fuzzy_find("meet", 10);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].score, found[1].score);
+        assert_eq!(found[0].path, paths[1]);
+        assert_eq!(found[0].field, FuzzyField::Title);
+        assert_eq!(&found[0].text[found[0].positions[0]..], "Meetings Archive");
+        assert_eq!(found[2].path, paths[2]);
+
+        let found = index.fuzzy_find("standup", 10);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].field, FuzzyField::Alias);
+        assert_eq!(
+            index.fuzzy_find("fuzzy/misc", 10)[0].field,
+            FuzzyField::Path
+        );
+
+        // Recently edited notes win otherwise equal matches
+        let i = index
+            .notes
+            .iter()
+            .position(|note| note.path == paths[1].as_string(true))
+            .unwrap();
+        index.notes[i].stamp.as_mut().unwrap().mtime = 0;
+        let found = index.fuzzy_find("meeting", 2);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].path, paths[0]);
+        assert!(index.fuzzy_find("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_file_stamp_same_content() {
+        let stamp = FileStamp {
+            mtime: 1,
+            size: 10,
+            hash: 42,
+        };
+        let touched = FileStamp { mtime: 2, ..stamp };
+        assert_ne!(stamp, touched);
+        assert!(stamp.same_content(&touched));
+        assert!(!stamp.same_content(&FileStamp { hash: 43, ..stamp }));
+    }
+
+    #[test]
+    fn test_index_refresh() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        // Notes without tags, which other tests leave untouched
+        let added = ScribePath::from("reference/attachments.md");
+        let updated = ScribePath::from("inbox/test_file4.md");
+        let removed = ScribePath::from("inbox/deleted_note.md");
+
+        index
+            .notes
+            .retain(|note| note.path != added.as_string(true));
+        let position = |index: &ScribeIndex, path: &ScribePath| {
+            index
+                .notes
+                .iter()
+                .position(|note| note.path == path.as_string(true))
+                .unwrap()
+        };
+        let i = position(&index, &updated);
+        let original = index.notes[i].stamp.unwrap();
+        index.notes[i].title = None;
+        index.notes[i].stamp = Some(FileStamp {
+            mtime: 0,
+            size: original.size,
+            hash: original.hash + 1,
+        });
+        let mut deleted = NoteInfo::parse(&removed);
+        deleted.stamp = Some(original);
+        index.notes.push(deleted);
+
+        let summary = index.refresh();
+        assert_eq!(summary.added, vec![added.clone()]);
+        assert!(summary.updated.contains(&updated));
+        assert_eq!(summary.removed, vec![removed.clone()]);
+        assert!(!index.in_index(&removed));
+        assert!(index.in_index(&added));
+
+        let i = position(&index, &updated);
+        assert_eq!(index.notes[i].title, Some("Test File 4".to_string()));
+        assert_eq!(index.notes[i].stamp, Some(original));
+
+        // A touched but unchanged file is re-stamped without being re-parsed
+        index.notes[i].stamp = Some(FileStamp {
+            mtime: 0,
+            ..original
+        });
+        index.notes[i].title = None;
+        let summary = index.refresh();
+        assert!(!summary.updated.contains(&updated));
+        assert!(index.notes[i].title.is_none());
+        assert!(index.notes[i].stamp.unwrap().mtime > 0);
+    }
+
+    #[test]
+    fn test_index_find_by_field() {
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        let mut test_file = ScribePath::root();
+        test_file.extend("inbox/test_file2.md");
+
+        let found = index.find_by_field("date", "2023-01-01 12:05 AM");
+        assert_eq!(found, vec![test_file]);
+        assert_eq!(index.find_with_field("date").len(), 2);
+        assert!(index.find_with_field("status").is_empty());
+    }
+
+    #[test]
+    fn test_note_info_get_field() {
+        let dir = TestDir::new("note_info_get_field");
+        let new_file = dir.path("test_get_field.md");
+        let test_data =
+            "---\nowner: alice\nstatus: draft\n---\n\nowner:: bob\nowner:: carol\nrating:: 4";
+        let res = new_file.create_file(test_data);
+        assert!(res.is_ok());
+
+        let mut note = NoteInfo::parse(&new_file);
+        note.extra
+            .insert("status".to_string(), serde_json::json!("done"));
+        let metadata = note.metadata();
+        for key in ["owner", "status", "rating", "missing"] {
+            assert_eq!(note.get_field(key).as_ref(), metadata.get(key), "{}", key);
+        }
+        assert_eq!(note.get_field("owner").unwrap().as_list().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_index_find_by_inline_field() {
+        let dir = TestDir::new("index_find_by_inline_field");
+        let new_file = dir.create(
+            "test_inline_fields.md",
+            "---\nowner: bob\n---\n\nowner:: alice\nreviewed in [inline_rating:: 4]",
+        );
+
+        let mut index = ScribeIndex::new();
+        index.insert(&new_file);
+
+        assert_eq!(
+            index.find_by_field("inline_rating", "4"),
+            vec![new_file.clone()]
+        );
+        assert_eq!(
+            index.find_by_field("owner", "alice"),
+            vec![new_file.clone()]
+        );
+        assert_eq!(index.find_by_field("owner", "bob"), vec![new_file.clone()]);
+        assert_eq!(index.find_with_field("inline_rating").len(), 1);
+    }
+
+    #[test]
+    fn test_note_info_created_modified() {
+        let dir = TestDir::new("note_info_created_modified");
+        let new_file = dir.create(
+            "test_created_modified.md",
+            "---\ncreated: 2026-10-01\nupdated: 2026-10-02T09:30:00+00:00\n---\n\n# Dates",
+        );
+
+        let note = NoteInfo::parse(&new_file);
+        assert_eq!(note.created, Date::parse_value("2026-10-01"));
+        assert_eq!(
+            note.modified,
+            Date::parse_value("2026-10-02 09:30:00 +0000")
+        );
+        assert_eq!(note.modified_at(), note.modified);
+
+        let res = new_file.create_file("# No Dates");
+        assert!(res.is_ok());
+
+        let note = NoteInfo::parse(&new_file);
+        assert!(note.created.is_none() && note.modified.is_none());
+        assert_eq!(note.modified_at(), new_file.modified());
+        assert!(note.created_at().is_some());
+    }
+
+    #[test]
+    fn test_index_nested_tags() {
+        let dir = TestDir::new("index_nested_tags");
+        let first_file = dir.create(
+            "test_nested_tags1.md",
+            "---\ntags: [nested/scribe]\n---\n\n#nested/scribe/backend and `#nested/scribe/code`",
+        );
+
+        let second_file = dir.create("test_nested_tags2.md", "#nested/other #nested/scribes");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&first_file);
+        index.insert(&second_file);
+
+        let tree = index.tag_tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].name.as_str(), tree[0].count), ("nested", 2));
+        let children: Vec<(&str, usize)> = tree[0]
+            .children
+            .iter()
+            .map(|node| (node.tag.as_str(), node.count))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                ("nested/other", 1),
+                ("nested/scribe", 1),
+                ("nested/scribes", 1)
+            ]
+        );
+        assert_eq!(tree[0].children[1].children[0].name, "backend");
+
+        assert_eq!(
+            index.find_by_tag_prefix("nested/scribe"),
+            vec![first_file.clone()]
+        );
+        assert_eq!(index.find_by_tag_prefix("#nested").len(), 2);
+
+        let renamed = index.rename_tag("nested/scribe", "nested/app").unwrap();
+        assert_eq!(renamed, vec![first_file.clone()]);
+        assert_eq!(
+            first_file.get_data().unwrap(),
+            "---\ntags: [nested/app]\n---\n\n#nested/app/backend and `#nested/scribe/code`"
+        );
+        assert!(index.find_by_tag_prefix("nested/scribe").is_empty());
+        assert_eq!(index.find_by_tag_prefix("nested/app").len(), 1);
+        assert_eq!(
+            second_file.get_data().unwrap(),
+            "#nested/other #nested/scribes"
+        );
+    }
+
+    #[test]
+    fn test_index_rename_tag_other_keys() {
+        let dir = TestDir::new("index_rename_tag_other_keys");
+        let new_file = dir.create("test_rename_tag_other_keys.md", "---\ndescription: tags for scribe\nsubtags: [scribe]\ntags: [notes, scribe]\n---\n\n#scribe");
+
+        let mut index = ScribeIndex::new();
+        index.insert(&new_file);
+
+        let renamed = index.rename_tag("scribe", "app").unwrap();
+        assert_eq!(renamed, vec![new_file.clone()]);
+        assert_eq!(
+            new_file.get_data().unwrap(),
+            "---\ndescription: tags for scribe\nsubtags: [scribe]\ntags: [notes, app]\n---\n\n#app"
+        );
+    }
+
+    #[test]
+    fn test_index_find_tag_occurrences() {
+        let dir = TestDir::new("index_find_tag_occurrences");
+        let new_file = dir.create(
+            "test_tag_occurrences.md",
+            "# Occurrences\n\n#occurrence and\n`#code` #occurrence",
+        );
+
+        let mut index = ScribeIndex::new();
+        index.insert(&new_file);
+
+        let occurrences = index.find_tag_occurrences("occurrence");
+        assert_eq!(occurrences.len(), 2);
+        assert_eq!(occurrences[0].0, new_file);
+        assert_eq!((occurrences[0].1.line, occurrences[0].1.column), (3, 2));
+        assert_eq!((occurrences[1].1.line, occurrences[1].1.column), (4, 10));
+    }
+
+    #[test]
+    fn test_index_load_vs_index() {
+        let dir = TestDir::new("index_load_vs_index");
+        let mut index_path = ScribePath::root();
+        index_path.extend("test_index.json");
+
+        // Dates written without an offset are read in the local timezone, so the fixture's dates
+        // are moved to the same wall clock time here before loading it
+        let mut fixture: serde_json::Value =
+            serde_json::from_str(&index_path.get_data().unwrap()).unwrap();
+        for note in fixture["notes"].as_array_mut().unwrap() {
+            for key in ["date", "created", "modified"] {
+                if let Some(written) = note.get(key).and_then(|date| date.as_str()) {
+                    let wall = DateTime::parse_from_rfc3339(written).unwrap().naive_local();
+                    let local = Local.from_local_datetime(&wall).unwrap();
+                    note[key] = serde_json::json!(local.to_rfc3339());
+                }
+            }
+        }
+        let fixture = dir.create("test_index.json", &fixture.to_string());
+        let loaded_index = ScribeIndex::load(Some(fixture));
+
+        assert!(loaded_index.is_some());
+
+        // Test Creating a New Index
+        let mut index = ScribeIndex::new();
+        index.index(None);
+
+        // Modification times depend on the checkout, content hashes don't
+        let mut unwrapped = loaded_index.unwrap();
+        for note in unwrapped.notes.iter_mut().chain(index.notes.iter_mut()) {
+            if let Some(stamp) = note.stamp.as_mut() {
+                stamp.mtime = 0;
+            }
+        }
         for note in &index.notes {
             assert!(unwrapped.notes.contains(&note), "{:?}", note);
         }
@@ -294,9 +2432,12 @@ mod tests {
         }
 
         // Test Writes and Loads
+        let location = dir.path("index.json");
+        index.storage = Some(Box::new(JsonStorage::new(location.as_pathbuf())));
         index.write();
+        index.storage = None;
 
-        let new_index = ScribeIndex::load(None).unwrap();
+        let new_index = ScribeIndex::load(Some(location)).unwrap();
         assert_eq!(index, new_index);
 
         // Test delete
@@ -1,8 +1,14 @@
 pub mod config;
+pub mod fuzzy;
+pub mod graph;
 pub mod index;
 pub mod note;
 pub mod parsers;
 pub mod path;
+pub mod query;
+pub mod resolver;
+pub mod search;
+pub mod storage;
 pub mod sync;
 pub mod template;
 
@@ -38,8 +38,7 @@ impl Note {
 
         if res.is_ok() {
             // Replace Links
-            let mut index = ScribeIndex::new();
-            index.index(None);
+            let mut index = ScribeIndex::load_or_index();
             let backlinks = index.get_backlinks(&og_path);
             for backlink in backlinks {
                 // Update the Backlinked File With Correct Path
@@ -73,7 +72,7 @@ mod tests {
     fn test_note_from_template() {
         // Create tmp path
         let mut path = ScribePath::root();
-        path.extend("tmp/test_new_note.md");
+        path.extend("tmp/note_from_template/test_new_note.md");
 
         // Get Basic template
         let library = ScribeTemplateLibrary::load();
@@ -86,22 +85,41 @@ mod tests {
 
         let new_note = Note::from_template(path, template, params);
         assert!(new_note.is_some());
+
+        let _res = new_note.unwrap().path.get_parent().delete();
     }
 
     #[test]
     fn test_note_transfer() {
+        // Transfers rewrite the vault's index, so put back whatever was there before
+        let saved: Vec<(ScribePath, Option<String>)> = [".scribe", ".scribe.search"]
+            .iter()
+            .map(|name| {
+                let mut location = ScribePath::root();
+                location.extend(name);
+                let data = location.get_data();
+                (location, data)
+            })
+            .collect();
+
         // Get Path of Existing Note
         let mut path = ScribePath::root();
-        path.extend("tmp/test_note_test.md");
+        path.extend("tmp/note_transfer/test_note_test.md");
         let _res = path.create_file("This is a test file");
 
         let mut new_path = ScribePath::root();
-        new_path.extend("tmp/test_note_moved.md");
+        new_path.extend("tmp/note_transfer/test_note_moved.md");
 
         let mut note = Note::from_path(path);
         let res = note.transfer(&new_path);
         assert!(res.is_ok());
 
-        let _res = new_path.delete();
+        let _res = new_path.get_parent().delete();
+        for (location, data) in saved {
+            let _res = match data {
+                Some(data) => std::fs::write(location.as_pathbuf(), data),
+                None => location.delete(),
+            };
+        }
     }
 }
@@ -0,0 +1,132 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::embedded_links::EmbeddedLinks;
+use crate::parsers::markdown::Markdown;
+use crate::parsers::parser::Parser;
+use crate::parsers::span::Span;
+use crate::path::ScribePath;
+
+lazy_static! {
+    static ref IMG_SRC: Regex =
+        Regex::new(r#"(?i)<img\b[^>]*?\bsrc\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))[^>]*>"#)
+            .unwrap();
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AttachmentSource {
+    Markdown,
+    WikiEmbed,
+    Html,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Attachment {
+    pub target: String,
+    pub source: AttachmentSource,
+    pub span: Span,
+}
+
+pub struct Attachments {}
+
+impl Attachments {
+    pub fn parse(data: &str) -> Option<Vec<Attachment>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Attachment>> {
+        let mut attachments: Vec<Attachment> = vec![];
+
+        // Get Markdown Images
+        for link in markdown.links().iter().filter(|link| link.is_image) {
+            if Self::is_local(&link.dest) {
+                attachments.push(Attachment {
+                    target: Parser::normalize(&Parser::percent_decode(&link.dest)),
+                    source: AttachmentSource::Markdown,
+                    span: markdown.span(link.range.clone()),
+                });
+            }
+        }
+
+        // Get Embedded Files, embedded notes are links rather than attachments
+        for link in EmbeddedLinks::locate(markdown) {
+            if link.value.is_embed && Self::is_file(&link.value.target) {
+                attachments.push(Attachment {
+                    target: link.value.target,
+                    source: AttachmentSource::WikiEmbed,
+                    span: link.span,
+                });
+            }
+        }
+
+        // Get Html Images
+        for (offset, html) in markdown.html() {
+            for cap in IMG_SRC.captures_iter(html).flatten() {
+                let src = (1..=3).find_map(|i| cap.get(i)).unwrap();
+                if Self::is_local(src.as_str()) {
+                    let full = cap.get(0).unwrap();
+                    attachments.push(Attachment {
+                        target: Parser::normalize(&Parser::percent_decode(src.as_str())),
+                        source: AttachmentSource::Html,
+                        span: markdown.span(offset + full.start()..offset + full.end()),
+                    });
+                }
+            }
+        }
+
+        if attachments.is_empty() {
+            return None;
+        }
+
+        attachments.sort_by_key(|attachment| attachment.span.start);
+        Some(attachments)
+    }
+
+    fn is_local(dest: &str) -> bool {
+        !dest.is_empty()
+            && !dest.starts_with("www.")
+            && !dest.starts_with("//")
+            && !dest.contains(':')
+            && !dest.contains("{{")
+    }
+
+    pub fn is_file(target: &str) -> bool {
+        ScribePath::from(target).is_attachment()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "# Assets\n\n![Diagram](assets/my%20diagram.png) ![remote](https://example.com/x.png)\n\n![[drawing.excalidraw]] ![[other note]] ![[note.md]]\n\n<p align='center'>\n<img width=300 src=\"assets/photo.jpg\">\n</p>\n\n`![code](assets/code.png)`";
+        let attachments = Attachments::parse(test_data).unwrap();
+
+        let parsed: Vec<(&str, AttachmentSource)> = attachments
+            .iter()
+            .map(|attachment| (attachment.target.as_str(), attachment.source))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("assets/my diagram.png", AttachmentSource::Markdown),
+                ("drawing.excalidraw", AttachmentSource::WikiEmbed),
+                ("assets/photo.jpg", AttachmentSource::Html),
+            ]
+        );
+        assert_eq!(
+            &test_data[attachments[2].span.range()],
+            "<img width=300 src=\"assets/photo.jpg\">"
+        );
+        assert_eq!(attachments[1].span.line, 5);
+    }
+
+    #[test]
+    fn test_parse_none() {
+        assert!(Attachments::parse("[[note]] and [link](notes/note.md)").is_none());
+        assert!(Attachments::parse("<img src=\"{{ IMAGE }}\">").is_none());
+    }
+}
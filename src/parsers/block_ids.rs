@@ -0,0 +1,61 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+
+use crate::parsers::markdown::Markdown;
+use crate::parsers::span::Located;
+
+lazy_static! {
+    static ref BLOCK_ID: Regex = Regex::new(r"(?m)(?:^|[ \t])(\^([A-Za-z0-9-]+))[ \t]*$").unwrap();
+}
+
+pub struct BlockIds {}
+
+impl BlockIds {
+    pub fn parse(data: &str) -> Option<Vec<Located<String>>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    // `^block-id` markers closing a paragraph, list item or line of their own
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Located<String>>> {
+        let mut ids: Vec<Located<String>> = vec![];
+        for (offset, text) in markdown.prose() {
+            for cap in BLOCK_ID.captures_iter(text).flatten() {
+                let marker = cap.get(1).unwrap();
+                let end = offset + cap.get(0).unwrap().end();
+
+                // Prose stops at inline markup, so the marker must also end the line in the file
+                let rest = &markdown.data[end..];
+                if !(rest.is_empty() || rest.starts_with('\n') || rest.starts_with("\r\n")) {
+                    continue;
+                }
+
+                let span = markdown.span(offset + marker.start()..offset + marker.end());
+                ids.push(Located::new(cap.get(2).unwrap().as_str().to_string(), span));
+            }
+        }
+
+        if ids.is_empty() {
+            return None;
+        }
+        Some(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_ids() {
+        let test_data = "A paragraph ^para-1\n\n- an item ^Item2\n- not at the end ^x **bold**\n\n```\ncode ^code\n```\n\nSee [^1] and `^inline`\n\n^standalone\n\n[^1]: Footnote";
+        let ids = BlockIds::parse(test_data).unwrap();
+
+        let values: Vec<&str> = ids.iter().map(|id| id.value.as_str()).collect();
+        assert_eq!(values, vec!["para-1", "Item2", "standalone"]);
+        assert_eq!(ids[0].span.line, 1);
+        assert_eq!(ids[0].span.column, 13);
+        assert_eq!(&test_data[ids[1].span.range()], "^Item2");
+
+        assert!(BlockIds::parse("No ids here, only 2^10").is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::markdown::Markdown;
+use crate::parsers::span::Span;
+
+lazy_static! {
+    static ref CALLOUT: Regex = Regex::new(r"^\[!([^\]\s]+)\]([+-]?)[ \t]*(.*)$").unwrap();
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Callout {
+    pub kind: String,
+    pub title: Option<String>,
+    pub body: String,
+    // Some(true) for `[!type]-`, Some(false) for `[!type]+`
+    pub folded: Option<bool>,
+    pub span: Span,
+}
+
+pub struct Callouts {}
+
+impl Callouts {
+    pub fn parse(data: &str) -> Option<Vec<Callout>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Callout>> {
+        let mut callouts: Vec<Callout> = vec![];
+        for quote in markdown.block_quotes() {
+            let text = &markdown.data[quote.range.clone()];
+            let mut lines = text.lines();
+
+            // The quote starts at its own marker, later lines repeat the outer ones
+            let first = Self::strip_markers(lines.next().unwrap_or(""), 1);
+            let Some(cap) = CALLOUT.captures(first.trim()).ok().flatten() else {
+                continue;
+            };
+
+            let body: Vec<&str> = lines
+                .map(|line| Self::strip_markers(line, quote.depth + 1).trim())
+                .collect();
+            let title = cap.get(3).unwrap().as_str().trim();
+            let folded = match cap.get(2).unwrap().as_str() {
+                "-" => Some(true),
+                "+" => Some(false),
+                _ => None,
+            };
+
+            callouts.push(Callout {
+                kind: cap.get(1).unwrap().as_str().to_lowercase(),
+                title: Some(title.to_string()).filter(|title| !title.is_empty()),
+                body: body.join("\n").trim().to_string(),
+                folded,
+                span: markdown.span(quote.range.clone()),
+            });
+        }
+
+        if callouts.is_empty() {
+            return None;
+        }
+
+        Some(callouts)
+    }
+
+    fn strip_markers(line: &str, count: usize) -> &str {
+        let mut line = line;
+        for _ in 0..count {
+            match line.trim_start().strip_prefix('>') {
+                Some(rest) => line = rest.strip_prefix(' ').unwrap_or(rest),
+                None => break,
+            }
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "# Review\n\n> [!warning] Check the API\n> The endpoint is\n> deprecated.\n\n> [!QUESTION]-\n> Who owns this?\n> > [!note]+ Nested\n> > Inner body\n\n> A plain quote\n\n```\n> [!tip] In code\n```";
+        let callouts = Callouts::parse(test_data).unwrap();
+
+        assert_eq!(callouts.len(), 3);
+        assert_eq!(callouts[0].kind, "warning");
+        assert_eq!(callouts[0].title, Some("Check the API".to_string()));
+        assert_eq!(callouts[0].body, "The endpoint is\ndeprecated.");
+        assert_eq!(callouts[0].folded, None);
+        assert_eq!(callouts[0].span.line, 3);
+
+        assert_eq!(callouts[1].kind, "question");
+        assert!(callouts[1].title.is_none());
+        assert_eq!(callouts[1].folded, Some(true));
+        assert!(callouts[1].body.starts_with("Who owns this?"));
+
+        assert_eq!(callouts[2].kind, "note");
+        assert_eq!(callouts[2].title, Some("Nested".to_string()));
+        assert_eq!(callouts[2].body, "Inner body");
+        assert_eq!(callouts[2].folded, Some(false));
+    }
+
+    #[test]
+    fn test_parse_none() {
+        assert!(Callouts::parse("> Just a quote\n> [!note] not first").is_none());
+    }
+}
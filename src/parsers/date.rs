@@ -1,44 +1,149 @@
+use crate::config::ScribeConfig;
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+pub const DEFAULT_FORMATS: [&str; 9] = [
+    "%Y-%m-%d %I:%M %p",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S %z",
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%d %B %Y",
+];
+
+lazy_static! {
+    static ref OPTIONS: DateOptions = DateOptions::load();
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DateOptions {
+    pub formats: Vec<String>,
+    // Zone applied to values without an offset, None uses the local zone
+    pub timezone: Option<FixedOffset>,
+}
+
+impl Default for DateOptions {
+    fn default() -> Self {
+        DateOptions {
+            formats: DEFAULT_FORMATS.iter().map(|f| f.to_string()).collect(),
+            timezone: None,
+        }
+    }
+}
+
+impl DateOptions {
+    // Reads `date_formats` (separated by `|`, tried before the defaults) and `timezone`
+    pub fn load() -> Self {
+        let cfg: ScribeConfig = ScribeConfig::load();
+        let mut options = DateOptions::default();
+
+        if let Some(formats) = cfg.get("date_formats") {
+            let mut formats: Vec<String> = formats
+                .split('|')
+                .map(|format| format.trim().to_string())
+                .filter(|format| !format.is_empty())
+                .collect();
+            formats.append(&mut options.formats);
+            options.formats = formats;
+        }
+
+        options.timezone = cfg.get("timezone").and_then(|tz| Self::parse_timezone(tz));
+        options
+    }
+
+    pub fn parse_timezone(value: &str) -> Option<FixedOffset> {
+        match value.trim() {
+            "" => None,
+            tz if tz.eq_ignore_ascii_case("local") => None,
+            tz if tz.eq_ignore_ascii_case("utc") || tz == "Z" => FixedOffset::east_opt(0),
+            tz => tz.parse::<FixedOffset>().ok(),
+        }
+    }
+
+    fn localize(&self, naive: NaiveDateTime) -> Option<DateTime<Local>> {
+        match self.timezone {
+            Some(tz) => Some(
+                tz.from_local_datetime(&naive)
+                    .single()?
+                    .with_timezone(&Local),
+            ),
+            None => Local.from_local_datetime(&naive).earliest(),
+        }
+    }
+}
+
 pub struct Date {}
 
 impl Date {
     pub fn parse(data: &str) -> Option<DateTime<Local>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<DateTime<Local>> {
         lazy_static! {
             static ref DATE: Regex = Regex::new("\\bdate: (.+)").unwrap();
         };
 
-        let matches = Parser::get_matches(&DATE, data);
+        // Default to Front Matter Date
+        if markdown.front_matter_block.is_some() {
+            let front_matter = markdown.front_matter.as_ref()?;
+            return front_matter.get("date").and_then(|date| date.as_date());
+        }
 
-        let date_str: String;
-        if matches.is_some() {
-            let found_matches = matches.unwrap();
-            if found_matches.len() > 0 {
-                date_str = found_matches
-                    .into_iter()
-                    .next()
-                    .unwrap()
-                    .trim_start_matches(r#"""#)
-                    .trim_end_matches(r#"""#)
-                    .to_string();
-                let naive = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %I:%M %p");
-                if naive.is_ok() {
-                    return Some(Local.from_local_datetime(&naive.unwrap()).unwrap());
-                }
-                return None;
+        let matches = Parser::get_prose_matches(&DATE, markdown)?;
+        let date_str = matches.into_iter().next()?;
+        Self::parse_value(&date_str)
+    }
+
+    pub fn parse_value(value: &str) -> Option<DateTime<Local>> {
+        Self::parse_value_with(value, &OPTIONS)
+    }
+
+    // Values carrying an offset keep their instant, date only values resolve to midnight
+    pub fn parse_value_with(value: &str, options: &DateOptions) -> Option<DateTime<Local>> {
+        let date_str = value.trim().trim_matches('"').trim_matches('\'');
+
+        if let Ok(date) = DateTime::parse_from_rfc3339(date_str) {
+            return Some(date.with_timezone(&Local));
+        }
+
+        for format in &options.formats {
+            if let Ok(date) = DateTime::parse_from_str(date_str, format) {
+                return Some(date.with_timezone(&Local));
+            }
+            if let Ok(naive) = NaiveDateTime::parse_from_str(date_str, format) {
+                return options.localize(naive);
+            }
+            if let Ok(naive) = NaiveDate::parse_from_str(date_str, format) {
+                return options.localize(naive.and_hms_opt(0, 0, 0)?);
             }
         }
 
-        return None;
+        None
+    }
+
+    // The calendar day of a value, without resolving a time zone for date only values
+    pub fn parse_day(value: &str) -> Option<NaiveDate> {
+        let date_str = value.trim().trim_matches('"').trim_matches('\'');
+        for format in &OPTIONS.formats {
+            if let Ok(naive) = NaiveDate::parse_from_str(date_str, format) {
+                return Some(naive);
+            }
+        }
+        Self::parse_value(date_str).map(|date| date.date_naive())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
 
     #[test]
     fn test_parse() {
@@ -61,4 +166,83 @@ mod tests {
         let parsed_date = Date::parse(test_data);
         assert!(parsed_date.is_none());
     }
+
+    #[test]
+    fn test_parse_front_matter() {
+        let naive_date =
+            NaiveDateTime::parse_from_str("2020-01-01 11:59 PM", "%Y-%m-%d %I:%M %p").unwrap();
+        let test_date = Local.from_local_datetime(&naive_date).unwrap();
+
+        let test_data = "---\ndate: \"2020-01-01 11:59 PM\"\n---\n\ndate: 2023-12-31 10:52 PM";
+        assert_eq!(Date::parse(test_data), Some(test_date));
+
+        // Body values are ignored once front matter is present
+        let test_data = "---\ntitle: Test\n---\n\ndate: 2023-12-31 10:52 PM";
+        assert!(Date::parse(test_data).is_none());
+    }
+
+    #[test]
+    fn test_parse_value_formats() {
+        let midnight = Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2026, 10, 1)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(Date::parse_value("2026-10-01"), Some(midnight));
+        assert_eq!(Date::parse_value("'2026/10/01'"), Some(midnight));
+        assert_eq!(Date::parse_value("1 October 2026"), Some(midnight));
+        assert_eq!(Date::parse_value("\"2026-10-01T00:00\""), Some(midnight));
+        assert_eq!(Date::parse_value("2026-10-01T00:00:00.000"), Some(midnight));
+
+        let utc = Utc.with_ymd_and_hms(2026, 10, 1, 8, 30, 0).unwrap();
+        assert_eq!(Date::parse_value("2026-10-01T08:30:00Z"), Some(utc.into()));
+        assert_eq!(
+            Date::parse_value("2026-10-01T10:30:00+02:00"),
+            Some(utc.into())
+        );
+        assert_eq!(
+            Date::parse_value("2026-10-01 10:30:00 +0200"),
+            Some(utc.into())
+        );
+
+        assert!(Date::parse_value("October").is_none());
+        assert!(Date::parse_value("2026-13-01").is_none());
+    }
+
+    #[test]
+    fn test_parse_value_with_options() {
+        let options = DateOptions {
+            formats: vec!["%d.%m.%Y %H:%M".to_string(), "%d.%m.%Y".to_string()],
+            timezone: DateOptions::parse_timezone("+02:00"),
+        };
+        let utc = Utc.with_ymd_and_hms(2026, 10, 1, 8, 30, 0).unwrap();
+        assert_eq!(
+            Date::parse_value_with("01.10.2026 10:30", &options),
+            Some(utc.into())
+        );
+        assert_eq!(
+            Date::parse_value_with("01.10.2026", &options),
+            Some(Utc.with_ymd_and_hms(2026, 9, 30, 22, 0, 0).unwrap().into())
+        );
+        assert!(Date::parse_value_with("2026-10-01", &options).is_none());
+
+        assert_eq!(DateOptions::parse_timezone("UTC"), FixedOffset::east_opt(0));
+        assert!(DateOptions::parse_timezone("local").is_none());
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(
+            Date::parse_day("2026-10-01"),
+            NaiveDate::from_ymd_opt(2026, 10, 1)
+        );
+        assert_eq!(
+            Date::parse_day("2026-10-01 11:59 PM"),
+            NaiveDate::from_ymd_opt(2026, 10, 1)
+        );
+        assert!(Date::parse_day("soon").is_none());
+    }
 }
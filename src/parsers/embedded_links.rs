@@ -1,29 +1,134 @@
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
+use crate::parsers::span::Located;
 use std::collections::HashSet;
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(from = "RawWikiLink")]
+pub struct WikiLink {
+    pub target: String,
+    pub heading: Option<String>,
+    pub block_id: Option<String>,
+    pub alias: Option<String>,
+    pub is_embed: bool,
+}
+
+// Older indexes stored wiki links as plain target strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawWikiLink {
+    Text(String),
+    Fields {
+        target: String,
+        heading: Option<String>,
+        block_id: Option<String>,
+        alias: Option<String>,
+        #[serde(default)]
+        is_embed: bool,
+    },
+}
+
+impl From<RawWikiLink> for WikiLink {
+    fn from(raw: RawWikiLink) -> Self {
+        match raw {
+            RawWikiLink::Text(text) => WikiLink::from_inner(&text, false),
+            RawWikiLink::Fields {
+                target,
+                heading,
+                block_id,
+                alias,
+                is_embed,
+            } => WikiLink {
+                target,
+                heading,
+                block_id,
+                alias,
+                is_embed,
+            },
+        }
+    }
+}
+
+impl WikiLink {
+    // Parses the text between the brackets, e.g. `note#Section|Display text`
+    pub fn from_inner(inner: &str, is_embed: bool) -> Self {
+        let (reference, alias) = match inner.split_once('|') {
+            Some((reference, alias)) => (reference.trim_end_matches('\\'), Some(alias)),
+            None => (inner, None),
+        };
+
+        let (target, mut heading, mut block_id) = match reference.split_once('#') {
+            Some((target, fragment)) => match fragment.strip_prefix('^') {
+                Some(block_id) => (target, None, Some(block_id)),
+                None => (target, Some(fragment), None),
+            },
+            None => (reference, None, None),
+        };
+
+        let target = match target.split_once('^') {
+            Some((target, block)) => {
+                block_id = Some(block);
+                heading = None;
+                target
+            }
+            None => target,
+        };
+
+        let clean = |value: &str| Some(value.trim().to_string()).filter(|value| !value.is_empty());
+        WikiLink {
+            target: Parser::normalize(target.trim()),
+            heading: heading.and_then(clean),
+            block_id: block_id.and_then(clean),
+            alias: alias.and_then(clean),
+            is_embed,
+        }
+    }
+
+    // The linked note path without any `.md` extension
+    pub fn target_path(&self) -> &str {
+        self.target.trim_end_matches(".md")
+    }
+}
+
 pub struct EmbeddedLinks {}
 
 impl EmbeddedLinks {
-    pub fn parse(data: &str) -> Option<HashSet<String>> {
+    pub fn parse(data: &str) -> Option<HashSet<WikiLink>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<HashSet<WikiLink>> {
+        let located = Self::locate(markdown);
+        if located.is_empty() {
+            return None;
+        }
+
+        Some(located.into_iter().map(|link| link.value).collect())
+    }
+
+    pub fn locate(markdown: &Markdown) -> Vec<Located<WikiLink>> {
         lazy_static! {
-            static ref EMBEDDED_LINKS: Regex =
-                Regex::new("\\[\\[([\\.a-zA-Z0-9/\\s_\\-]+)[|]?[\\]\\]]?").unwrap();
+            static ref EMBEDDED_LINKS: Regex = Regex::new(r"(!?)\[\[([^\[\]\n]+)\]\]").unwrap();
         };
-        let matches = Parser::get_matches(&EMBEDDED_LINKS, data);
 
-        if matches.is_some() {
-            let mut full_matches: HashSet<String> = HashSet::new();
-            for match_ in matches.unwrap() {
-                if !match_.starts_with("www") & !match_.starts_with("http") {
-                    full_matches.insert(match_.trim().to_string());
+        let mut located: Vec<Located<WikiLink>> = vec![];
+        for (offset, text) in markdown.prose() {
+            for cap in EMBEDDED_LINKS.captures_iter(text).flatten() {
+                let full = cap.get(0).unwrap();
+                let is_embed = !cap.get(1).unwrap().as_str().is_empty();
+                let link = WikiLink::from_inner(cap.get(2).unwrap().as_str(), is_embed);
+
+                if !link.target.starts_with("www") & !link.target.starts_with("http") {
+                    let span = markdown.span(offset + full.start()..offset + full.end());
+                    located.push(Located::new(link, span));
                 }
             }
-            return Some(full_matches);
         }
-        return None;
+        located
     }
 }
 
@@ -44,6 +149,77 @@ mod tests {
         let unwrapped = matches.unwrap();
         println!("{:?}", unwrapped);
         assert!(unwrapped.len() == 2);
-        assert_eq!(unwrapped, test_links);
+        let targets: HashSet<String> = unwrapped.into_iter().map(|link| link.target).collect();
+        assert_eq!(targets, test_links);
+
+        // Test 2
+        let test_string = "`[[projects/test/code]]`\n\n```\n[[projects/test/block]]\n```\n\n[[projects/test/scribe]]";
+        let matches = EmbeddedLinks::parse(test_string);
+        assert_eq!(
+            matches,
+            Some(HashSet::from([WikiLink::from_inner(
+                "projects/test/scribe",
+                false
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_wiki_link_from_inner() {
+        let link = WikiLink::from_inner("note#Section|Display text", false);
+        assert_eq!(link.target, "note");
+        assert_eq!(link.heading, Some("Section".to_string()));
+        assert_eq!(link.alias, Some("Display text".to_string()));
+        assert!(link.block_id.is_none());
+
+        let link = WikiLink::from_inner("note^block-id", true);
+        assert_eq!(link.target, "note");
+        assert_eq!(link.block_id, Some("block-id".to_string()));
+        assert!(link.is_embed);
+
+        let link = WikiLink::from_inner("folder/note.md#^block-id", false);
+        assert_eq!(link.target_path(), "folder/note");
+        assert_eq!(link.block_id, Some("block-id".to_string()));
+        assert!(link.heading.is_none());
+
+        let link = WikiLink::from_inner("日本語/会議メモ#議題|Gro\u{308}ße", false);
+        assert_eq!(link.target, "日本語/会議メモ");
+        assert_eq!(link.heading, Some("議題".to_string()));
+        assert_eq!(link.alias, Some("Gro\u{308}ße".to_string()));
+        assert_eq!(
+            WikiLink::from_inner("cafe\u{301}", false).target,
+            WikiLink::from_inner("café", false).target
+        );
+
+        let link = WikiLink::from_inner("#Local Heading", false);
+        assert_eq!(link.target, "");
+        assert_eq!(link.heading, Some("Local Heading".to_string()));
+    }
+
+    #[test]
+    fn test_wiki_link_deserialize() {
+        let link: WikiLink = serde_json::from_str(r#""note#Section""#).unwrap();
+        assert_eq!(link, WikiLink::from_inner("note#Section", false));
+
+        let embed = WikiLink::from_inner("diagram.png|300", true);
+        let json = serde_json::to_string(&embed).unwrap();
+        let loaded: WikiLink = serde_json::from_str(&json).unwrap();
+        assert_eq!(embed, loaded);
+    }
+
+    #[test]
+    fn test_locate() {
+        let test_string =
+            "A link [[projects/test/scribe]]\nand [[www.google.com]] ![[ diagram.png | 300]]";
+        let located = EmbeddedLinks::locate(&Markdown::parse(test_string));
+
+        assert_eq!(located.len(), 2);
+        assert_eq!(located[0].value.target, "projects/test/scribe");
+        assert_eq!((located[0].span.line, located[0].span.column), (1, 8));
+        assert_eq!(
+            &test_string[located[1].span.range()],
+            "![[ diagram.png | 300]]"
+        );
+        assert!(located[1].value.is_embed);
     }
 }
@@ -0,0 +1,122 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::markdown::Markdown;
+use crate::parsers::span::Span;
+
+lazy_static! {
+    static ref UNDEFINED_REFERENCE: Regex = Regex::new(r"\[\^([^\[\]\s]+)\]").unwrap();
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Footnote {
+    pub label: String,
+    pub text: Option<String>,
+    pub definition: Option<Span>,
+    pub references: Vec<Span>,
+}
+
+impl Footnote {
+    pub fn is_defined(&self) -> bool {
+        self.definition.is_some()
+    }
+}
+
+pub struct Footnotes {}
+
+impl Footnotes {
+    pub fn parse(data: &str) -> Option<Vec<Footnote>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Footnote>> {
+        let mut footnotes: Vec<Footnote> = vec![];
+        for definition in markdown.footnotes() {
+            let footnote = Self::entry(&mut footnotes, &definition.label);
+            footnote.text = Some(definition.text.clone());
+            footnote.definition = Some(markdown.span(definition.range.clone()));
+        }
+
+        for reference in markdown.footnote_references() {
+            let span = markdown.span(reference.range.clone());
+            Self::entry(&mut footnotes, &reference.label)
+                .references
+                .push(span);
+        }
+
+        // References without a definition are left as plain text
+        for (offset, text) in markdown.prose() {
+            for cap in UNDEFINED_REFERENCE.captures_iter(text).flatten() {
+                let full = cap.get(0).unwrap();
+                let span = markdown.span(offset + full.start()..offset + full.end());
+                Self::entry(&mut footnotes, cap.get(1).unwrap().as_str())
+                    .references
+                    .push(span);
+            }
+        }
+
+        if footnotes.is_empty() {
+            return None;
+        }
+
+        for footnote in footnotes.iter_mut() {
+            footnote.references.sort_by_key(|span| span.start);
+        }
+        Some(footnotes)
+    }
+
+    fn entry<'a>(footnotes: &'a mut Vec<Footnote>, label: &str) -> &'a mut Footnote {
+        let index = match footnotes
+            .iter()
+            .position(|footnote| footnote.label == label)
+        {
+            Some(index) => index,
+            None => {
+                footnotes.push(Footnote {
+                    label: label.to_string(),
+                    text: None,
+                    definition: None,
+                    references: vec![],
+                });
+                footnotes.len() - 1
+            }
+        };
+        &mut footnotes[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "Claim[^1] and another[^source], again[^1] and [^missing].\n\n`[^code]`\n\n[^1]: The first *note*\n    continued\n\n[^source]: See `paper`";
+        let footnotes = Footnotes::parse(test_data).unwrap();
+
+        let labels: Vec<&str> = footnotes
+            .iter()
+            .map(|footnote| footnote.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["1", "source", "missing"]);
+
+        assert_eq!(
+            footnotes[0].text,
+            Some("The first note continued".to_string())
+        );
+        assert_eq!(footnotes[0].definition.unwrap().line, 5);
+        assert_eq!(footnotes[0].references.len(), 2);
+        assert_eq!(&test_data[footnotes[0].references[0].range()], "[^1]");
+
+        assert_eq!(footnotes[1].text, Some("See paper".to_string()));
+
+        assert!(!footnotes[2].is_defined());
+        assert_eq!(&test_data[footnotes[2].references[0].range()], "[^missing]");
+    }
+
+    #[test]
+    fn test_parse_none() {
+        assert!(Footnotes::parse("No notes here, [link](x.md) and ^caret").is_none());
+    }
+}
@@ -0,0 +1,486 @@
+use crate::parsers::date::Date;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FrontMatterFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl FromStr for FrontMatterFormat {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_lowercase().as_str() {
+            "yaml" | "yml" => Ok(FrontMatterFormat::Yaml),
+            "toml" => Ok(FrontMatterFormat::Toml),
+            "json" => Ok(FrontMatterFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FrontMatterBlock<'a> {
+    pub format: FrontMatterFormat,
+    pub raw: &'a str,
+    pub offset: usize,
+}
+
+impl FrontMatterBlock<'_> {
+    pub fn parse(&self) -> Option<FrontMatter> {
+        match self.format {
+            FrontMatterFormat::Yaml => FrontMatter::from_yaml(self.raw),
+            FrontMatterFormat::Toml => FrontMatter::from_toml(self.raw),
+            FrontMatterFormat::Json => FrontMatter::from_json(self.raw),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    List(Vec<FieldValue>),
+    Map(BTreeMap<String, FieldValue>),
+}
+
+impl FieldValue {
+    fn from_yaml(value: serde_yaml::Value) -> Self {
+        match value {
+            serde_yaml::Value::Null => FieldValue::Null,
+            serde_yaml::Value::Bool(b) => FieldValue::Bool(b),
+            serde_yaml::Value::Number(n) => match n.as_i64() {
+                Some(i) => FieldValue::Integer(i),
+                None => FieldValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_yaml::Value::String(s) => FieldValue::String(s),
+            serde_yaml::Value::Sequence(seq) => {
+                FieldValue::List(seq.into_iter().map(Self::from_yaml).collect())
+            }
+            serde_yaml::Value::Mapping(mapping) => {
+                let mut map: BTreeMap<String, FieldValue> = BTreeMap::new();
+                for (key, value) in mapping {
+                    if let Some(key) = Self::yaml_key(&key) {
+                        map.insert(key, Self::from_yaml(value));
+                    }
+                }
+                FieldValue::Map(map)
+            }
+            serde_yaml::Value::Tagged(tagged) => Self::from_yaml(tagged.value),
+        }
+    }
+
+    fn yaml_key(key: &serde_yaml::Value) -> Option<String> {
+        match key {
+            serde_yaml::Value::String(s) => Some(s.to_string()),
+            serde_yaml::Value::Bool(b) => Some(b.to_string()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    fn from_toml(value: toml::Value) -> Self {
+        match value {
+            toml::Value::String(s) => FieldValue::String(s),
+            toml::Value::Integer(i) => FieldValue::Integer(i),
+            toml::Value::Float(f) => FieldValue::Float(f),
+            toml::Value::Boolean(b) => FieldValue::Bool(b),
+            toml::Value::Datetime(datetime) => FieldValue::String(datetime.to_string()),
+            toml::Value::Array(array) => {
+                FieldValue::List(array.into_iter().map(Self::from_toml).collect())
+            }
+            toml::Value::Table(table) => FieldValue::Map(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (key, Self::from_toml(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => FieldValue::Null,
+            serde_json::Value::Bool(b) => FieldValue::Bool(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => FieldValue::Integer(i),
+                None => FieldValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => FieldValue::String(s),
+            serde_json::Value::Array(array) => {
+                FieldValue::List(array.into_iter().map(Self::from_json).collect())
+            }
+            serde_json::Value::Object(object) => FieldValue::Map(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, Self::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    // Types an untyped value, such as the right hand side of an inline `key:: value` field
+    pub fn from_text(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            return FieldValue::Null;
+        }
+        if let Ok(b) = text.parse::<bool>() {
+            return FieldValue::Bool(b);
+        }
+        if let Ok(i) = text.parse::<i64>() {
+            return FieldValue::Integer(i);
+        }
+        if let Ok(f) = text.parse::<f64>() {
+            if f.is_finite() {
+                return FieldValue::Float(f);
+            }
+        }
+        FieldValue::String(text.to_string())
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FieldValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    // Dates stay as written and are only parsed here, so a title or alias that looks like a date
+    // is still text
+    pub fn as_date(&self) -> Option<DateTime<Local>> {
+        match self {
+            FieldValue::String(s) => Date::parse_value(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<FieldValue>> {
+        match self {
+            FieldValue::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&BTreeMap<String, FieldValue>> {
+        match self {
+            FieldValue::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    // Scalar values rendered as plain text, lists and maps have no text form
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            FieldValue::Bool(b) => Some(b.to_string()),
+            FieldValue::Integer(i) => Some(i.to_string()),
+            FieldValue::Float(f) => Some(f.to_string()),
+            FieldValue::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    // A repeated field, collected into a list in the order it was found
+    pub fn append(self, value: FieldValue) -> FieldValue {
+        match self {
+            FieldValue::List(mut list) => {
+                list.push(value);
+                FieldValue::List(list)
+            }
+            existing => FieldValue::List(vec![existing, value]),
+        }
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            FieldValue::Null => value.is_empty(),
+            FieldValue::Bool(b) => value.parse::<bool>().map(|v| v == *b).unwrap_or(false),
+            FieldValue::Integer(i) => value.parse::<i64>().map(|v| v == *i).unwrap_or(false),
+            FieldValue::Float(f) => value.parse::<f64>().map(|v| v == *f).unwrap_or(false),
+            FieldValue::String(s) => {
+                s == value
+                    || Date::parse_value(s)
+                        .is_some_and(|date| Date::parse_value(value) == Some(date))
+            }
+            FieldValue::List(list) => list.iter().any(|item| item.matches(value)),
+            FieldValue::Map(_) => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(transparent)]
+pub struct FrontMatter {
+    fields: BTreeMap<String, FieldValue>,
+}
+
+impl FrontMatter {
+    // Splits a leading `---` (YAML), `+++` (TOML) or `{` (JSON) block from the body
+    pub fn split(data: &str) -> (Option<FrontMatterBlock<'_>>, &str) {
+        let trimmed = data.trim_start_matches(['\u{feff}', '\n', '\r', ' ', '\t']);
+        let offset = data.len() - trimmed.len();
+
+        if trimmed.starts_with('{') {
+            return Self::split_json(data, offset);
+        }
+
+        let mut lines = trimmed.split_inclusive('\n');
+        let first = match lines.next() {
+            Some(line) => line,
+            None => return (None, data),
+        };
+        let (format, fences): (FrontMatterFormat, &[&str]) = match first.trim_end() {
+            "---" => (FrontMatterFormat::Yaml, &["---", "..."]),
+            "+++" => (FrontMatterFormat::Toml, &["+++"]),
+            _ => return (None, data),
+        };
+
+        let block_start = offset + first.len();
+        let mut position = block_start;
+        for line in lines {
+            if fences.contains(&line.trim_end()) {
+                let raw = &data[block_start..position];
+                let body = &data[position + line.len()..];
+                let block = FrontMatterBlock {
+                    format,
+                    raw,
+                    offset: block_start,
+                };
+                return (Some(block), body);
+            }
+            position += line.len();
+        }
+
+        (None, data)
+    }
+
+    fn split_json(data: &str, offset: usize) -> (Option<FrontMatterBlock<'_>>, &str) {
+        let mut stream =
+            serde_json::Deserializer::from_str(&data[offset..]).into_iter::<serde_json::Value>();
+        match stream.next() {
+            Some(Ok(serde_json::Value::Object(_))) => {
+                let end = offset + stream.byte_offset();
+                let raw = &data[offset..end];
+                let body = &data[end..];
+                (
+                    Some(FrontMatterBlock {
+                        format: FrontMatterFormat::Json,
+                        raw,
+                        offset,
+                    }),
+                    body,
+                )
+            }
+            _ => (None, data),
+        }
+    }
+
+    pub fn parse(data: &str) -> Option<Self> {
+        let (block, _) = Self::split(data);
+        block?.parse()
+    }
+
+    pub fn from_yaml(raw: &str) -> Option<Self> {
+        let value: serde_yaml::Value = serde_yaml::from_str(raw).ok()?;
+        match FieldValue::from_yaml(value) {
+            FieldValue::Map(fields) => Some(Self { fields }),
+            FieldValue::Null => Some(Self::default()),
+            _ => None,
+        }
+    }
+
+    pub fn from_toml(raw: &str) -> Option<Self> {
+        let table: toml::Table = toml::from_str(raw).ok()?;
+        match FieldValue::from_toml(toml::Value::Table(table)) {
+            FieldValue::Map(fields) => Some(Self { fields }),
+            _ => None,
+        }
+    }
+
+    pub fn from_json(raw: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(raw).ok()?;
+        match FieldValue::from_json(value) {
+            FieldValue::Map(fields) => Some(Self { fields }),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        self.fields.get(key)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|value| value.as_str())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.fields.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.fields.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldValue)> {
+        self.fields.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    // Repeated keys are collected into a list rather than overwritten
+    pub fn merge(&mut self, key: &str, value: FieldValue) {
+        let merged = match self.fields.remove(key) {
+            None => value,
+            Some(existing) => existing.append(value),
+        };
+        self.fields.insert(key.to_string(), merged);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let test_data = "---\ntitle: Test\n---\n\n# Body";
+        let (block, body) = FrontMatter::split(test_data);
+        let block = block.unwrap();
+        assert_eq!(block.format, FrontMatterFormat::Yaml);
+        assert_eq!(block.raw, "title: Test\n");
+        assert_eq!(body, "\n# Body");
+
+        let test_data = "+++\ntitle = \"Test\"\n+++\n\n# Body";
+        let (block, body) = FrontMatter::split(test_data);
+        let block = block.unwrap();
+        assert_eq!(block.format, FrontMatterFormat::Toml);
+        assert_eq!(block.raw, "title = \"Test\"\n");
+        assert_eq!(body, "\n# Body");
+
+        let test_data = "{\"title\": \"Test {braces}\"}\n\n# Body";
+        let (block, body) = FrontMatter::split(test_data);
+        let block = block.unwrap();
+        assert_eq!(block.format, FrontMatterFormat::Json);
+        assert_eq!(block.raw, "{\"title\": \"Test {braces}\"}");
+        assert_eq!(body, "\n\n# Body");
+
+        // Unterminated blocks are not front matter
+        let test_data = "---\ntitle: Test\n# Body";
+        let (block, body) = FrontMatter::split(test_data);
+        assert!(block.is_none());
+        assert_eq!(body, test_data);
+
+        // Front matter must lead the file
+        let test_data = "# Body\n---\ntitle: Test\n---";
+        assert!(FrontMatter::split(test_data).0.is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let test_data = "---\ntitle: Test File\ndate: 2023-01-01 12:05 AM\npriority: 2\nweight: 0.5\ndraft: false\naliases: [first, second]\nproject:\n  name: scribe\n  status: active\n---\n\nbody";
+        let front_matter = FrontMatter::parse(test_data).unwrap();
+
+        assert_eq!(front_matter.len(), 7);
+        assert_eq!(front_matter.get_str("title"), Some("Test File"));
+        assert!(front_matter.get("date").unwrap().as_date().is_some());
+        assert_eq!(front_matter.get("priority"), Some(&FieldValue::Integer(2)));
+        assert_eq!(front_matter.get("weight"), Some(&FieldValue::Float(0.5)));
+        assert_eq!(front_matter.get("draft"), Some(&FieldValue::Bool(false)));
+        assert_eq!(
+            front_matter
+                .get("aliases")
+                .unwrap()
+                .as_list()
+                .unwrap()
+                .len(),
+            2
+        );
+        let project = front_matter.get("project").unwrap().as_map().unwrap();
+        assert_eq!(project["status"].as_str(), Some("active"));
+
+        assert!(front_matter.get("aliases").unwrap().matches("second"));
+        assert!(front_matter.get("priority").unwrap().matches("2"));
+        assert!(!front_matter.get("draft").unwrap().matches("true"));
+    }
+
+    #[test]
+    fn test_parse_dialects() {
+        let yaml =
+            "---\ntitle: Test\ndate: 2023-01-01\ntags: [a, b]\nproject:\n  status: active\n---";
+        let toml = "+++\ntitle = \"Test\"\ndate = 2023-01-01\ntags = [\"a\", \"b\"]\n[project]\nstatus = \"active\"\n+++";
+        let json = r#"{"title": "Test", "date": "2023-01-01", "tags": ["a", "b"], "project": {"status": "active"}}"#;
+
+        let from_yaml = FrontMatter::parse(yaml).unwrap();
+        assert_eq!(from_yaml.len(), 4);
+        assert_eq!(FrontMatter::parse(toml), Some(from_yaml.clone()));
+        assert_eq!(FrontMatter::parse(json), Some(from_yaml));
+
+        // Invalid blocks are detected but not parsed
+        assert!(FrontMatter::split("+++\ntitle = \n+++").0.is_some());
+        assert!(FrontMatter::parse("+++\ntitle = \n+++").is_none());
+        assert!(FrontMatter::parse("[1, 2]").is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut front_matter = FrontMatter::parse("---\nowner: alice\ntags: [a]\n---").unwrap();
+        front_matter.merge("owner", FieldValue::from_text("bob"));
+        front_matter.merge("tags", FieldValue::from_text("b"));
+        front_matter.merge("rating", FieldValue::from_text("4"));
+
+        assert!(front_matter.get("owner").unwrap().matches("alice"));
+        assert!(front_matter.get("owner").unwrap().matches("bob"));
+        assert_eq!(
+            front_matter.get("tags").unwrap().as_list().unwrap().len(),
+            2
+        );
+        assert_eq!(front_matter.get("rating"), Some(&FieldValue::Integer(4)));
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let test_data = "---\ntitle: Test\ndate: 2023-01-01\ntags: [a, b]\n---";
+        let front_matter = FrontMatter::parse(test_data).unwrap();
+
+        let json = serde_json::to_string(&front_matter).unwrap();
+        let loaded: FrontMatter = serde_json::from_str(&json).unwrap();
+        assert_eq!(front_matter, loaded);
+    }
+
+    #[test]
+    fn test_parse_date_like_strings() {
+        let test_data = "---
+title: 2026-10-18
+aliases: [18 October 2026]
+due: 2026/10/18
+---";
+        let front_matter = FrontMatter::parse(test_data).unwrap();
+
+        // Values that look like dates are kept as written, and only parsed when asked
+        let title = front_matter.get("title").unwrap();
+        assert_eq!(title.as_string(), Some("2026-10-18".to_string()));
+        assert!(title.as_date().is_some());
+        let aliases = front_matter.get("aliases").unwrap().as_list().unwrap();
+        assert_eq!(aliases[0].as_str(), Some("18 October 2026"));
+        assert!(front_matter.get("due").unwrap().matches("2026-10-18"));
+        assert_eq!(
+            crate::parsers::title::Title::parse(test_data),
+            Some("2026-10-18".to_string())
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::parsers::markdown::Markdown;
+use crate::parsers::parser::Parser;
+use crate::parsers::span::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+    pub span: Span,
+}
+
+impl Heading {
+    // Matches `[[note#Heading]]` style references by text or by anchor
+    pub fn matches(&self, reference: &str) -> bool {
+        let text = Parser::normalize(&self.text).to_lowercase();
+        text == Parser::normalize(reference.trim()).to_lowercase()
+            || self.anchor == Headings::slugify(reference)
+    }
+}
+
+pub struct Headings {}
+
+impl Headings {
+    pub fn parse(data: &str) -> Option<Vec<Heading>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Heading>> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut headings: Vec<Heading> = vec![];
+        for heading in markdown.headings() {
+            // Repeated anchors are suffixed -1, -2... as GitHub does
+            let slug = Self::slugify(&heading.text);
+            let count = seen.entry(slug.clone()).or_insert(0);
+            let anchor = match *count {
+                0 => slug,
+                n => format!("{}-{}", slug, n),
+            };
+            *count += 1;
+
+            headings.push(Heading {
+                level: heading.level,
+                text: heading.text.clone(),
+                anchor,
+                span: markdown.span(heading.range.clone()),
+            });
+        }
+
+        if headings.is_empty() {
+            return None;
+        }
+
+        Some(headings)
+    }
+
+    pub fn slugify(text: &str) -> String {
+        let mut slug = String::new();
+        for c in Parser::normalize(text.trim()).chars() {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                slug.extend(c.to_lowercase());
+            } else if c.is_whitespace() {
+                slug.push('-');
+            }
+        }
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "# Title\n\n## First Section\n\ntext\n\n### Sub-section: Details!\n\n```\n# not a heading\n```\n\n## First Section";
+        let headings = Headings::parse(test_data).unwrap();
+
+        let outline: Vec<(u8, &str, &str)> = headings
+            .iter()
+            .map(|heading| {
+                (
+                    heading.level,
+                    heading.text.as_str(),
+                    heading.anchor.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (1, "Title", "title"),
+                (2, "First Section", "first-section"),
+                (3, "Sub-section: Details!", "sub-section-details"),
+                (2, "First Section", "first-section-1"),
+            ]
+        );
+        assert_eq!(headings[2].span.line, 7);
+
+        assert!(Headings::parse("No headings here").is_none());
+    }
+
+    #[test]
+    fn test_heading_matches() {
+        let headings = Headings::parse("## Sub-section: Details!").unwrap();
+        assert!(headings[0].matches("Sub-section: Details!"));
+        assert!(headings[0].matches("sub-section-details"));
+        assert!(!headings[0].matches("details"));
+
+        let headings = Headings::parse("## Größe Überblick").unwrap();
+        assert_eq!(headings[0].anchor, "größe-überblick");
+        assert!(headings[0].matches("GRO\u{308}ßE ÜBERBLICK"));
+        assert!(headings[0].matches("gro\u{308}ße-überblick"));
+    }
+}
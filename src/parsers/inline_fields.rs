@@ -0,0 +1,141 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::front_matter::FieldValue;
+use crate::parsers::markdown::Markdown;
+use crate::parsers::parser::Parser;
+use crate::parsers::span::Span;
+
+lazy_static! {
+    static ref FULL_LINE_FIELD: Regex =
+        Regex::new(r"(?m)^[ \t]*\**([\w][\w \t\-/]*?)\**::(?=[ \t]|$)[ \t]*(.*?)[ \t]*$").unwrap();
+    static ref BRACKETED_FIELD: Regex =
+        Regex::new(r"[\[\(]([\w][\w \t\-/]*?)::[ \t]*([^\]\)\n]*?)[ \t]*[\]\)]").unwrap();
+    static ref LINE_PREFIX: Regex =
+        Regex::new(r"^[ \t>]*(?:(?:[-*+]|\d+[.)])[ \t]+)?(?:\[.\][ \t]+)?$").unwrap();
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct InlineField {
+    pub key: String,
+    pub value: FieldValue,
+    pub span: Span,
+}
+
+pub struct InlineFields {}
+
+impl InlineFields {
+    pub fn parse(data: &str) -> Option<Vec<InlineField>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<InlineField>> {
+        let mut fields: Vec<InlineField> = vec![];
+        for (offset, text) in markdown.prose() {
+            // Bracketed fields: [key:: value] or (key:: value)
+            let mut bracketed: Vec<(usize, usize)> = vec![];
+            for cap in BRACKETED_FIELD.captures_iter(text).flatten() {
+                let full = cap.get(0).unwrap();
+                bracketed.push((full.start(), full.end()));
+                if Self::is_key(cap.get(1).unwrap().as_str()) {
+                    fields.push(Self::field(markdown, offset, &cap));
+                }
+            }
+
+            // Full line fields: key:: value
+            for cap in FULL_LINE_FIELD.captures_iter(text).flatten() {
+                let full = cap.get(0).unwrap();
+                let inside_bracket = bracketed
+                    .iter()
+                    .any(|(start, end)| *start < full.end() && full.start() < *end);
+                if !inside_bracket
+                    && Self::is_key(cap.get(1).unwrap().as_str())
+                    && Self::starts_line(markdown, offset + full.start())
+                {
+                    fields.push(Self::field(markdown, offset, &cap));
+                }
+            }
+        }
+
+        if fields.is_empty() {
+            return None;
+        }
+
+        fields.sort_by_key(|field| field.span.start);
+        Some(fields)
+    }
+
+    fn field(markdown: &Markdown, offset: usize, cap: &fancy_regex::Captures) -> InlineField {
+        let full = cap.get(0).unwrap();
+        InlineField {
+            key: Parser::normalize(cap.get(1).unwrap().as_str().trim()),
+            value: FieldValue::from_text(cap.get(2).unwrap().as_str()),
+            span: markdown.span(offset + full.start()..offset + full.end()),
+        }
+    }
+
+    // A key ending in a number is more likely a time or ratio, as in `Meeting at 10::30`
+    fn is_key(key: &str) -> bool {
+        key.split_whitespace()
+            .last()
+            .is_some_and(|word| !word.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    // Prose can begin mid-line, e.g. after inline code, so check what precedes it
+    fn starts_line(markdown: &Markdown, offset: usize) -> bool {
+        let line_start = markdown.data[..offset].rfind('\n').map_or(0, |i| i + 1);
+        LINE_PREFIX
+            .is_match(&markdown.data[line_start..offset])
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "# Review\n\nowner:: alice\n- status:: in progress\n\nRated [rating:: 4] and (done:: true) today\n\n`inline code` fake:: field\n\n```\ncode:: block\n```";
+        let fields = InlineFields::parse(test_data).unwrap();
+
+        let parsed: Vec<(&str, &FieldValue)> = fields
+            .iter()
+            .map(|field| (field.key.as_str(), &field.value))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("owner", &FieldValue::String("alice".to_string())),
+                ("status", &FieldValue::String("in progress".to_string())),
+                ("rating", &FieldValue::Integer(4)),
+                ("done", &FieldValue::Bool(true)),
+            ]
+        );
+        assert_eq!(&test_data[fields[2].span.range()], "[rating:: 4]");
+        assert_eq!((fields[1].span.line, fields[1].span.column), (4, 3));
+    }
+
+    #[test]
+    fn test_parse_none() {
+        assert!(InlineFields::parse(":: a separator without a key").is_none());
+        assert!(InlineFields::parse("No fields here: just prose").is_none());
+        assert!(InlineFields::parse("Meeting at 10:: 30").is_none());
+        assert!(InlineFields::parse("Meeting at 10::30").is_none());
+        assert!(InlineFields::parse("ratio::3").is_none());
+        assert!(InlineFields::parse("Call (room 4:: 12) later").is_none());
+    }
+
+    #[test]
+    fn test_parse_key_with_digits() {
+        let fields = InlineFields::parse(
+            "q3 goal:: ship
+step 2b::
+",
+        )
+        .unwrap();
+        let keys: Vec<&str> = fields.iter().map(|field| field.key.as_str()).collect();
+        assert_eq!(keys, vec!["q3 goal", "step 2b"]);
+    }
+}
@@ -1,30 +1,42 @@
-use fancy_regex::Regex;
-use lazy_static::lazy_static;
-
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
+use crate::parsers::span::Located;
 use std::collections::HashSet;
 
 pub struct InternalLinks {}
 
 impl InternalLinks {
     pub fn parse(data: &str) -> Option<HashSet<String>> {
-        lazy_static! {
-            static ref INTERNAL_LINKS: Regex =
-                Regex::new("\\[.+\\]\\(([A-Za-z0-9\\-/\\._]+)\\)").unwrap();
-        };
-
-        let matches = Parser::get_matches(&INTERNAL_LINKS, data);
-
-        if matches.is_some() {
-            let mut full_matches: HashSet<String> = HashSet::new();
-            for match_ in matches.unwrap() {
-                if !match_.starts_with("http") & !match_.starts_with("www.") {
-                    full_matches.insert(match_);
-                }
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<HashSet<String>> {
+        let links: HashSet<String> = Self::locate(markdown)
+            .into_iter()
+            .map(|link| link.value)
+            .filter(|link| !link.starts_with('#'))
+            .collect();
+        Some(links).filter(|links| !links.is_empty())
+    }
+
+    // Includes anchors within the note, such as `#heading`, which `from_markdown` leaves out
+    pub fn locate(markdown: &Markdown) -> Vec<Located<String>> {
+        let mut located: Vec<Located<String>> = vec![];
+        for link in markdown.links() {
+            if !link.is_image && Self::is_internal(&link.dest) {
+                let span = markdown.span(link.range.clone());
+                let dest = Parser::normalize(&Parser::percent_decode(&link.dest));
+                located.push(Located::new(dest, span));
             }
-            return Some(full_matches);
         }
-        return None;
+        located
+    }
+
+    fn is_internal(dest: &str) -> bool {
+        !dest.is_empty()
+            && !dest.starts_with("http")
+            && !dest.starts_with("www.")
+            && !dest.contains(':')
     }
 }
 
@@ -36,8 +48,7 @@ mod tests {
     #[test]
     fn test_parse() {
         let mut test_links: HashSet<String> = HashSet::new();
-        // TODO: This link only pulls the first match for some reason
-        // test_links.insert("projects/test/test/scribe".to_string());
+        test_links.insert("projects/test/test/scribe".to_string());
         test_links.insert("inbox/scribe/test.txt".to_string());
 
         // Test
@@ -45,5 +56,32 @@ mod tests {
         let parsed_links = InternalLinks::parse(test_data);
         assert!(parsed_links.is_some());
         assert_eq!(parsed_links.unwrap(), test_links);
+
+        // Test links inside code are ignored
+        let test_data = "`[link](projects/code.md)`\n\n    [link](projects/block.md)\n\n[anchor](#heading) [mail](mailto:me@example.com)";
+        assert!(InternalLinks::parse(test_data).is_none());
+
+        // Test percent encoded and decomposed file names
+        let test_data = "[Größe](notes/Gr%C3%B6%C3%9Fe.md) [café](notes/cafe\u{301}.md) [会議](notes/会議メモ.md)";
+        let test_links: HashSet<String> = HashSet::from([
+            "notes/Größe.md".to_string(),
+            "notes/café.md".to_string(),
+            "notes/会議メモ.md".to_string(),
+        ]);
+        assert_eq!(InternalLinks::parse(test_data), Some(test_links));
+    }
+
+    #[test]
+    fn test_locate() {
+        let test_data = "# Title\n\nSee [the note](inbox/note.md) for details";
+        let located = InternalLinks::locate(&Markdown::parse(test_data));
+
+        assert_eq!(located.len(), 1);
+        assert_eq!(located[0].value, "inbox/note.md");
+        assert_eq!(
+            &test_data[located[0].span.range()],
+            "[the note](inbox/note.md)"
+        );
+        assert_eq!((located[0].span.line, located[0].span.column), (3, 5));
     }
 }
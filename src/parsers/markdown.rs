@@ -0,0 +1,358 @@
+use crate::parsers::front_matter::{FrontMatter, FrontMatterBlock};
+use crate::parsers::span::{LineIndex, Span};
+use pulldown_cmark::{Event, Options, Parser as CmarkParser, Tag, TagEnd};
+use std::ops::Range;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownHeading {
+    pub level: u8,
+    pub text: String,
+    pub range: Range<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownLink {
+    pub dest: String,
+    // The link text or image alt text, which isn't prose
+    pub text: String,
+    pub range: Range<usize>,
+    pub is_image: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownTask {
+    pub checked: bool,
+    pub depth: usize,
+    pub text: String,
+    pub range: Range<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownBlockQuote {
+    pub depth: usize,
+    pub range: Range<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownFootnote {
+    pub label: String,
+    pub text: String,
+    pub range: Range<usize>,
+}
+
+// A parsed note, exposing only the prose regions that parsers should scan.
+// Code blocks, inline code, html, math and link destinations are never prose.
+pub struct Markdown<'a> {
+    pub data: &'a str,
+    pub front_matter_block: Option<FrontMatterBlock<'a>>,
+    pub front_matter: Option<FrontMatter>,
+    line_index: LineIndex,
+    prose: Vec<Range<usize>>,
+    headings: Vec<MarkdownHeading>,
+    links: Vec<MarkdownLink>,
+    tasks: Vec<MarkdownTask>,
+    html: Vec<Range<usize>>,
+    block_quotes: Vec<MarkdownBlockQuote>,
+    footnotes: Vec<MarkdownFootnote>,
+    footnote_references: Vec<MarkdownFootnote>,
+}
+
+impl<'a> Markdown<'a> {
+    pub fn parse(data: &'a str) -> Self {
+        let (front_matter_block, body) = FrontMatter::split(data);
+        let front_matter = front_matter_block.and_then(|block| block.parse());
+        let offset = data.len() - body.len();
+
+        let mut markdown = Self {
+            data,
+            front_matter_block,
+            front_matter,
+            line_index: LineIndex::new(data),
+            prose: vec![],
+            headings: vec![],
+            links: vec![],
+            tasks: vec![],
+            html: vec![],
+            block_quotes: vec![],
+            footnotes: vec![],
+            footnote_references: vec![],
+        };
+        markdown.walk(body, offset);
+        markdown
+    }
+
+    fn options() -> Options {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_MATH);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TASKLISTS);
+        options.insert(Options::ENABLE_FOOTNOTES);
+        options
+    }
+
+    fn walk(&mut self, body: &str, offset: usize) {
+        let mut in_code_block = false;
+        let mut link_depth: usize = 0;
+        let mut heading: Option<MarkdownHeading> = None;
+        let mut items: Vec<(usize, Option<MarkdownTask>)> = vec![];
+        let mut quote_depth: usize = 0;
+        let mut footnote: Option<MarkdownFootnote> = None;
+
+        for (event, range) in CmarkParser::new_ext(body, Self::options()).into_offset_iter() {
+            let range = (range.start + offset)..(range.end + offset);
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Start(Tag::Heading { level, .. }) => {
+                    heading = Some(MarkdownHeading {
+                        level: level as u8,
+                        text: String::new(),
+                        range,
+                    });
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    if let Some(mut finished) = heading.take() {
+                        finished.text = finished.text.trim().to_string();
+                        self.headings.push(finished);
+                    }
+                }
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    link_depth += 1;
+                    self.links.push(MarkdownLink {
+                        dest: dest_url.to_string(),
+                        text: String::new(),
+                        range,
+                        is_image: false,
+                    });
+                }
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    link_depth += 1;
+                    self.links.push(MarkdownLink {
+                        dest: dest_url.to_string(),
+                        text: String::new(),
+                        range,
+                        is_image: true,
+                    });
+                }
+                Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => {
+                    link_depth = link_depth.saturating_sub(1);
+                }
+                Event::Start(Tag::BlockQuote(_)) => {
+                    self.block_quotes.push(MarkdownBlockQuote {
+                        depth: quote_depth,
+                        range,
+                    });
+                    quote_depth += 1;
+                }
+                Event::End(TagEnd::BlockQuote(_)) => quote_depth = quote_depth.saturating_sub(1),
+                Event::Start(Tag::FootnoteDefinition(label)) => {
+                    footnote = Some(MarkdownFootnote {
+                        label: label.to_string(),
+                        text: String::new(),
+                        range,
+                    });
+                }
+                Event::End(TagEnd::FootnoteDefinition) => {
+                    if let Some(mut finished) = footnote.take() {
+                        finished.text = finished.text.trim().to_string();
+                        self.footnotes.push(finished);
+                    }
+                }
+                Event::FootnoteReference(label) => {
+                    self.footnote_references.push(MarkdownFootnote {
+                        label: label.to_string(),
+                        text: String::new(),
+                        range,
+                    });
+                }
+                Event::Start(Tag::Item) => items.push((range.start, None)),
+                Event::End(TagEnd::Item) => {
+                    if let Some((_, Some(mut task))) = items.pop() {
+                        task.text = task.text.trim().to_string();
+                        self.tasks.push(task);
+                    }
+                }
+                Event::TaskListMarker(checked) => {
+                    let depth = items.len().saturating_sub(1);
+                    if let Some((start, task)) = items.last_mut() {
+                        let line_end = self.data[*start..]
+                            .find('\n')
+                            .map_or(self.data.len(), |end| *start + end);
+                        *task = Some(MarkdownTask {
+                            checked,
+                            depth,
+                            text: String::new(),
+                            range: *start..line_end,
+                        });
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(heading) = heading.as_mut() {
+                        heading.text.push_str(&text);
+                    }
+                    if let Some((_, Some(task))) = items.last_mut() {
+                        task.text.push_str(&text);
+                    }
+                    if let Some(footnote) = footnote.as_mut() {
+                        footnote.text.push_str(&text);
+                    }
+                    if !in_code_block && link_depth == 0 {
+                        self.push_prose(range);
+                    } else if let Some(link) = self.links.last_mut().filter(|_| link_depth > 0) {
+                        link.text.push_str(&text);
+                    }
+                }
+                Event::Code(code) => {
+                    if let Some(heading) = heading.as_mut() {
+                        heading.text.push_str(&code);
+                    }
+                    if let Some((_, Some(task))) = items.last_mut() {
+                        task.text.push_str(&code);
+                    }
+                    if let Some(footnote) = footnote.as_mut() {
+                        footnote.text.push_str(&code);
+                    }
+                }
+                Event::Html(_) | Event::InlineHtml(_) => match self.html.last_mut() {
+                    Some(last) if last.end == range.start => last.end = range.end,
+                    _ => self.html.push(range),
+                },
+                Event::SoftBreak | Event::HardBreak => {
+                    if let Some((_, Some(task))) = items.last_mut() {
+                        task.text.push(' ');
+                    }
+                    if let Some(footnote) = footnote.as_mut() {
+                        footnote.text.push(' ');
+                    }
+                    if link_depth == 0 {
+                        self.push_prose(range);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.tasks.sort_by_key(|task| task.range.start);
+    }
+
+    fn push_prose(&mut self, range: Range<usize>) {
+        if let Some(last) = self.prose.last_mut() {
+            if last.end == range.start {
+                last.end = range.end;
+                return;
+            }
+        }
+        self.prose.push(range);
+    }
+
+    // Each contiguous prose region, paired with its byte offset into the file
+    pub fn prose(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.prose
+            .iter()
+            .map(|range| (range.start, &self.data[range.clone()]))
+    }
+
+    pub fn span(&self, range: Range<usize>) -> Span {
+        self.line_index.span(self.data, range)
+    }
+
+    pub fn headings(&self) -> &[MarkdownHeading] {
+        &self.headings
+    }
+
+    pub fn links(&self) -> &[MarkdownLink] {
+        &self.links
+    }
+
+    pub fn tasks(&self) -> &[MarkdownTask] {
+        &self.tasks
+    }
+
+    pub fn block_quotes(&self) -> &[MarkdownBlockQuote] {
+        &self.block_quotes
+    }
+
+    pub fn footnotes(&self) -> &[MarkdownFootnote] {
+        &self.footnotes
+    }
+
+    // References to defined footnotes, undefined ones are left as prose
+    pub fn footnote_references(&self) -> &[MarkdownFootnote] {
+        &self.footnote_references
+    }
+
+    // Raw html regions, paired with their byte offset into the file
+    pub fn html(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.html
+            .iter()
+            .map(|range| (range.start, &self.data[range.clone()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prose_skips_code() {
+        let test_data = "---\ntitle: Test\n---\n\n# Heading `code`\n\nSome *prose* #tag\n\n```c\n#include <stdio.h>\n```\n\nInline `#not_tag` and $#math$ <!-- #comment -->\n\n    #indented";
+        let markdown = Markdown::parse(test_data);
+
+        let prose: String = markdown.prose().map(|(_, text)| text).collect();
+        assert!(prose.contains("#tag"));
+        assert!(!prose.contains("include"));
+        assert!(!prose.contains("not_tag"));
+        assert!(!prose.contains("math"));
+        assert!(!prose.contains("comment"));
+        assert!(!prose.contains("indented"));
+        assert!(!prose.contains("title"));
+
+        for (offset, text) in markdown.prose() {
+            assert_eq!(&test_data[offset..offset + text.len()], text);
+        }
+
+        assert_eq!(markdown.headings().len(), 1);
+        assert_eq!(markdown.headings()[0].level, 1);
+        assert_eq!(markdown.headings()[0].text, "Heading code");
+        assert!(markdown.front_matter.is_some());
+    }
+
+    #[test]
+    fn test_links() {
+        let test_data = "A [link](inbox/note.md), an ![image](assets/x.png) and <https://www.google.com>\n\n`[code](not/a/link.md)`";
+        let markdown = Markdown::parse(test_data);
+
+        let dests: Vec<&str> = markdown
+            .links()
+            .iter()
+            .map(|link| link.dest.as_str())
+            .collect();
+        assert_eq!(
+            dests,
+            vec!["inbox/note.md", "assets/x.png", "https://www.google.com"]
+        );
+        assert!(markdown.links()[1].is_image);
+
+        let prose: String = markdown.prose().map(|(_, text)| text).collect();
+        assert!(!prose.contains("google"));
+    }
+
+    #[test]
+    fn test_html() {
+        let test_data =
+            "<p align='center'>\n<img src=\"a.png\">\n</p>\n\nInline <img src=\"b.png\"> html";
+        let markdown = Markdown::parse(test_data);
+
+        let html: Vec<&str> = markdown.html().map(|(_, html)| html).collect();
+        assert_eq!(
+            html,
+            vec![
+                "<p align='center'>\n<img src=\"a.png\">\n</p>\n",
+                "<img src=\"b.png\">"
+            ]
+        );
+        for (offset, html) in markdown.html() {
+            assert_eq!(&test_data[offset..offset + html.len()], html);
+        }
+    }
+}
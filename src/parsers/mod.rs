@@ -1,7 +1,18 @@
+pub mod attachments;
+pub mod block_ids;
+pub mod callouts;
 pub mod date;
 pub mod embedded_links;
+pub mod footnotes;
+pub mod front_matter;
+pub mod headings;
+pub mod inline_fields;
 pub mod internal_links;
+pub mod markdown;
 pub mod parser;
+pub mod registry;
+pub mod span;
 pub mod tags;
+pub mod tasks;
 pub mod title;
 pub mod web_links;
@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
+use crate::parsers::markdown::Markdown;
+use crate::parsers::span::Located;
 use fancy_regex::Regex;
+use unicode_normalization::UnicodeNormalization;
 
 pub struct Parser {}
 
@@ -20,6 +23,67 @@ impl Parser {
 
         return None;
     }
+
+    // Canonical (NFC) form, so composed and decomposed text compare equal
+    pub fn normalize(text: &str) -> String {
+        text.nfc().collect()
+    }
+
+    // Markdown links to non-ASCII file names are usually percent encoded
+    pub fn percent_decode(dest: &str) -> String {
+        if !dest.contains('%') {
+            return dest.to_string();
+        }
+
+        let bytes = dest.as_bytes();
+        let mut decoded: Vec<u8> = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let hex = dest.get(i + 1..i + 3);
+            match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) if bytes[i] == b'%' => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                _ => {
+                    decoded.push(bytes[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8(decoded).unwrap_or_else(|_| dest.to_string())
+    }
+
+    pub fn get_prose_matches(matcher: &Regex, markdown: &Markdown) -> Option<HashSet<String>> {
+        let mut res: HashSet<String> = HashSet::new();
+        for (_, text) in markdown.prose() {
+            if let Some(matches) = Self::get_matches(matcher, text) {
+                res.extend(matches);
+            }
+        }
+
+        if res.is_empty() {
+            return None;
+        }
+
+        Some(res)
+    }
+
+    pub fn locate_prose_matches(matcher: &Regex, markdown: &Markdown) -> Vec<Located<String>> {
+        let mut res: Vec<Located<String>> = vec![];
+        for (offset, text) in markdown.prose() {
+            for cap in matcher.captures_iter(text).flatten() {
+                let group = cap.get(1).unwrap();
+                let value = group.as_str().trim();
+                let start = offset
+                    + group.start()
+                    + (group.as_str().len() - group.as_str().trim_start().len());
+                let span = markdown.span(start..start + value.len());
+                res.push(Located::new(value.to_string(), span));
+            }
+        }
+        res
+    }
 }
 
 #[cfg(test)]
@@ -35,4 +99,35 @@ mod tests {
         assert!(matches.is_some());
         assert!(matches.unwrap().contains("Finn!"));
     }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Parser::normalize("Gro\u{308}ße"), "Größe");
+        assert_eq!(Parser::normalize("会議メモ"), "会議メモ");
+    }
+
+    #[test]
+    fn test_get_prose_matches() {
+        let markdown = Markdown::parse(
+            "Hi my name is Finn!
+
+`Hi my name is Jake`",
+        );
+        let re: Regex = Regex::new("Hi my name is (.+)").unwrap();
+        let matches = Parser::get_prose_matches(&re, &markdown);
+
+        assert_eq!(matches, Some(HashSet::from(["Finn!".to_string()])));
+    }
+
+    #[test]
+    fn test_locate_prose_matches() {
+        let markdown = Markdown::parse("`Hi my name is Jake`\n\nHi my name is Finn!");
+        let re: Regex = Regex::new("Hi my name is (.+)").unwrap();
+        let located = Parser::locate_prose_matches(&re, &markdown);
+
+        assert_eq!(located.len(), 1);
+        assert_eq!(located[0].value, "Finn!");
+        assert_eq!(located[0].span.start, 36);
+        assert_eq!((located[0].span.line, located[0].span.column), (3, 15));
+    }
 }
@@ -0,0 +1,103 @@
+use crate::parsers::markdown::Markdown;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// An extractor for data the builtin parsers don't cover, stored under `name` in `NoteInfo.extra`
+pub trait NoteParser: Send + Sync {
+    fn name(&self) -> &str;
+    fn parse(&self, markdown: &Markdown) -> Option<Value>;
+}
+
+#[derive(Default)]
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn NoteParser>>,
+}
+
+impl ParserRegistry {
+    // Replaces any parser already registered under the same name
+    pub fn register(&mut self, parser: Box<dyn NoteParser>) {
+        self.parsers
+            .retain(|existing| existing.name() != parser.name());
+        self.parsers.push(parser);
+    }
+
+    pub fn unregister(&mut self, name: &str) {
+        self.parsers.retain(|parser| parser.name() != name);
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.parsers.iter().map(|parser| parser.name()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    pub fn parse(&self, markdown: &Markdown) -> BTreeMap<String, Value> {
+        let mut extra: BTreeMap<String, Value> = BTreeMap::new();
+        for parser in &self.parsers {
+            if let Some(value) = parser.parse(markdown) {
+                extra.insert(parser.name().to_string(), value);
+            }
+        }
+        extra
+    }
+}
+
+impl PartialEq for ParserRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.names() == other.names()
+    }
+}
+
+impl fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fancy_regex::Regex;
+    use serde_json::json;
+
+    struct TicketParser {}
+
+    impl NoteParser for TicketParser {
+        fn name(&self) -> &str {
+            "tickets"
+        }
+
+        fn parse(&self, markdown: &Markdown) -> Option<Value> {
+            let ticket = Regex::new(r"\b[A-Z]+-\d+\b").unwrap();
+            let mut tickets: Vec<String> = vec![];
+            for (_, text) in markdown.prose() {
+                for found in ticket.find_iter(text).flatten() {
+                    tickets.push(found.as_str().to_string());
+                }
+            }
+            Some(json!(tickets)).filter(|_| !tickets.is_empty())
+        }
+    }
+
+    #[test]
+    fn test_registry_parse() {
+        let mut registry = ParserRegistry::default();
+        assert!(registry.is_empty());
+
+        registry.register(Box::new(TicketParser {}));
+        registry.register(Box::new(TicketParser {}));
+        assert_eq!(registry.names(), vec!["tickets"]);
+
+        let markdown = Markdown::parse("Fixes ABC-123 and XY-9\n\n`ZZ-1`");
+        let extra = registry.parse(&markdown);
+        assert_eq!(extra.get("tickets"), Some(&json!(["ABC-123", "XY-9"])));
+
+        assert!(registry.parse(&Markdown::parse("Nothing here")).is_empty());
+
+        registry.unregister("tickets");
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// Byte offsets into the file, with a 1-based line and character column
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Located<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Located<T> {
+    pub fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+}
+
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(data: &str) -> Self {
+        let mut line_starts: Vec<usize> = vec![0];
+        for (i, byte) in data.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        Self { line_starts }
+    }
+
+    pub fn span(&self, data: &str, range: Range<usize>) -> Span {
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= range.start);
+        let line_start = self.line_starts[line - 1];
+        let column = data[line_start..range.start].chars().count() + 1;
+        Span {
+            start: range.start,
+            end: range.end,
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index_span() {
+        let test_data = "first line\nsécond #tag\n\nlast";
+        let index = LineIndex::new(test_data);
+
+        let span = index.span(test_data, 0..5);
+        assert_eq!((span.line, span.column), (1, 1));
+
+        let start = test_data.find("#tag").unwrap();
+        let span = index.span(test_data, start..start + 4);
+        assert_eq!((span.line, span.column), (2, 8));
+        assert_eq!(&test_data[span.range()], "#tag");
+
+        let start = test_data.find("last").unwrap();
+        let span = index.span(test_data, start..start + 4);
+        assert_eq!((span.line, span.column), (4, 1));
+    }
+}
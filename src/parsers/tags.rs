@@ -1,60 +1,242 @@
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+use crate::parsers::front_matter::{FieldValue, FrontMatterBlock, FrontMatterFormat};
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
+use crate::parsers::span::Located;
 use std::collections::HashSet;
+use std::ops::Range;
+
+lazy_static! {
+    static ref TAGS_1: Regex = Regex::new(r#"\btags:\s\[([^\]]*)\]"#).unwrap();
+    static ref TAGS_2: Regex = Regex::new(r"(?<![\w'])\#([\w\-]+(?:/[\w\-]+)*)").unwrap();
+    static ref YAML_KEY: Regex = Regex::new(r#"(?m)^(?:tags|"tags"|'tags')[ \t]*:"#).unwrap();
+    static ref TOML_KEY: Regex = Regex::new(r#"(?m)^[ \t]*(?:tags|"tags"|'tags')[ \t]*="#).unwrap();
+    static ref JSON_KEY: Regex = Regex::new(r#""tags"\s*:"#).unwrap();
+    static ref WIKI_LINK: Regex = Regex::new(r"\[\[[^\[\]\n]*\]\]").unwrap();
+}
 
 pub struct Tags {}
 
 impl Tags {
     pub fn parse(data: &str) -> Option<HashSet<String>> {
-        lazy_static! {
-            static ref TAGS_1: Regex = Regex::new(r#"\btags:\s\[([^\]]*)\]"#).unwrap();
-            static ref TAGS_2: Regex = Regex::new(r"(?<!')\#([A-Z0-9a-z\-\_]+)").unwrap();
-        };
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<HashSet<String>> {
+        let tags: HashSet<String> = Self::locate(markdown)
+            .into_iter()
+            .map(|tag| tag.value)
+            .collect();
+
+        if tags.is_empty() {
+            return None;
+        }
+
+        Some(tags)
+    }
+
+    // Hashtags in a plain snippet of prose, in order of appearance
+    pub fn hashtags(text: &str) -> Vec<String> {
+        let mut tags: Vec<String> = vec![];
+        for (_, tag) in Self::find_hashtags(text) {
+            let tag = Parser::normalize(tag);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    // Each hashtag and where its name starts. A `#` within a wiki link starts a heading, as in
+    // `[[#Heading]]`, and a name of only digits such as `#123` is not a tag.
+    fn find_hashtags(text: &str) -> Vec<(usize, &str)> {
+        let links: Vec<Range<usize>> = WIKI_LINK
+            .find_iter(text)
+            .flatten()
+            .map(|link| link.range())
+            .collect();
+        TAGS_2
+            .captures_iter(text)
+            .flatten()
+            .filter_map(|cap| cap.get(1))
+            .filter(|tag| !links.iter().any(|link| link.contains(&tag.start())))
+            .filter(|tag| !tag.as_str().chars().all(|c| c.is_numeric() || c == '/'))
+            .map(|tag| (tag.start(), tag.as_str()))
+            .collect()
+    }
+
+    // Every level of a nested tag, e.g. `a`, `a/b` and `a/b/c` for `a/b/c`
+    pub fn ancestors(tag: &str) -> Vec<&str> {
+        tag.match_indices('/')
+            .map(|(i, _)| &tag[..i])
+            .chain(std::iter::once(tag))
+            .collect()
+    }
+
+    pub fn is_under(tag: &str, prefix: &str) -> bool {
+        let tag = Parser::normalize(tag);
+        let prefix = Parser::normalize(prefix.trim_start_matches('#').trim_end_matches('/'));
+        tag == prefix
+            || tag
+                .strip_prefix(&prefix)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    pub fn locate(markdown: &Markdown) -> Vec<Located<String>> {
+        let mut tags: Vec<Located<String>> = vec![];
 
         // Get Front Matter Tags
-        let matches = Parser::get_matches(&TAGS_1, data);
-
-        let mut tags = HashSet::<String>::new();
-        if matches.is_some() {
-            for match_ in matches.unwrap() {
-                if match_.contains(",") {
-                    for tag_item in match_.split(",") {
-                        tags.insert(
-                            tag_item
-                                .trim()
-                                .trim_start_matches(r#"""#)
-                                .trim_end_matches(r#"""#)
-                                .to_string(),
-                        );
-                    }
-                } else {
-                    tags.insert(
-                        match_
-                            .trim()
-                            .trim_start_matches(r#"""#)
-                            .trim_end_matches(r#"""#)
-                            .to_string(),
-                    );
+        if let Some(block) = markdown.front_matter_block {
+            let front_matter = markdown.front_matter.as_ref();
+            if let Some(value) = front_matter.and_then(|fm| fm.get("tags")) {
+                let mut values: Vec<String> = vec![];
+                Self::insert_field_value(&mut values, value);
+
+                // Point each tag at its own item within the value of the `tags` key
+                let (key, value_range) = Self::front_matter_value(&block).unwrap_or((0..0, 0..0));
+                let mut search_from = value_range.start;
+                for value in values {
+                    let region = &block.raw[search_from..value_range.end];
+                    let (start, end) = match Self::find_item(region, &value) {
+                        Some(i) => (search_from + i, search_from + i + value.len()),
+                        None => (key.start, key.end),
+                    };
+                    search_from = end.max(search_from);
+                    let span = markdown.span(block.offset + start..block.offset + end);
+                    tags.push(Located::new(Parser::normalize(&value), span));
+                }
+            }
+        } else {
+            for list in Parser::locate_prose_matches(&TAGS_1, markdown) {
+                for (offset, tag) in Self::split_list(&list.value) {
+                    let start = list.span.start + offset;
+                    let span = markdown.span(start..start + tag.len());
+                    tags.push(Located::new(Parser::normalize(tag), span));
                 }
             }
         }
 
-        // Get Hashtag Tags
-        let matches = Parser::get_matches(&TAGS_2, data);
-
-        if matches.is_some() {
-            for match_ in matches.unwrap() {
-                tags.insert(match_.to_string());
+        // Get Hashtag Tags, link text is never prose
+        for (offset, text) in markdown.prose() {
+            for (start, tag) in Self::find_hashtags(text) {
+                let span = markdown.span(offset + start..offset + start + tag.len());
+                tags.push(Located::new(Parser::normalize(tag), span));
             }
         }
 
-        if tags.len() == 0 {
-            return None;
+        tags
+    }
+
+    // The `tags` key at the top level of the block and the text holding its value
+    fn front_matter_value(block: &FrontMatterBlock) -> Option<(Range<usize>, Range<usize>)> {
+        let key = match block.format {
+            FrontMatterFormat::Yaml => &*YAML_KEY,
+            FrontMatterFormat::Toml => &*TOML_KEY,
+            FrontMatterFormat::Json => &*JSON_KEY,
+        };
+        let key = key.find(block.raw).ok().flatten()?.range();
+
+        let raw = block.raw;
+        let rest = &raw[key.end..];
+        let start = key.end + rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let value = &raw[start..];
+        let end = match value.chars().next() {
+            // A flow list, up to its closing bracket
+            Some('[') => {
+                let mut depth = 0;
+                let mut quote: Option<char> = None;
+                let mut end = raw.len();
+                for (i, c) in value.char_indices() {
+                    match (quote, c) {
+                        (Some(q), c) if c == q => quote = None,
+                        (Some(_), _) => {}
+                        (None, '"' | '\'') => quote = Some(c),
+                        (None, '[') => depth += 1,
+                        (None, ']') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                end = start + i + 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                end
+            }
+            // A single string
+            Some(q @ ('"' | '\'')) => value[1..].find(q).map_or(raw.len(), |i| start + i + 2),
+            // A plain value on the same line
+            Some(c) if c != '\n' && c != '\r' => value.find('\n').map_or(raw.len(), |i| start + i),
+            // A YAML block list, up to the next line at the top level
+            _ => {
+                let mut end = start;
+                for line in value.split_inclusive('\n').skip(1) {
+                    if !(line.trim().is_empty() || line.starts_with([' ', '\t', '-'])) {
+                        break;
+                    }
+                    end += line.len();
+                }
+                let first_line = value.find('\n').map_or(value.len(), |i| i + 1);
+                (end + first_line).min(raw.len())
+            }
+        };
+        Some((key, start..end))
+    }
+
+    // The first occurrence of `value` as a whole list item or string, not inside another tag
+    fn find_item(region: &str, value: &str) -> Option<usize> {
+        region.match_indices(value).map(|(i, _)| i).find(|&i| {
+            let before = region[..i].chars().next_back();
+            let after = region[i + value.len()..]
+                .trim_start_matches('/')
+                .chars()
+                .next();
+            before.is_none_or(|c| c.is_whitespace() || "[,\"'#-".contains(c))
+                && after.is_none_or(|c| c.is_whitespace() || ",]\"'".contains(c))
+        })
+    }
+
+    fn insert_field_value(tags: &mut Vec<String>, value: &FieldValue) {
+        match value {
+            FieldValue::List(items) => {
+                for item in items {
+                    Self::insert_field_value(tags, item);
+                }
+            }
+            FieldValue::String(list) => {
+                for (_, tag) in Self::split_list(list) {
+                    tags.push(tag.to_string());
+                }
+            }
+            _ => {
+                if let Some(tag) = value.as_string() {
+                    tags.push(tag);
+                }
+            }
         }
+    }
 
-        return Some(tags);
+    // Splits a comma separated list into cleaned tags and their byte offsets
+    fn split_list(list: &str) -> Vec<(usize, &str)> {
+        let mut items: Vec<(usize, &str)> = vec![];
+        let mut offset = 0;
+        for tag_item in list.split(',') {
+            let tag = tag_item
+                .trim()
+                .trim_start_matches('"')
+                .trim_end_matches('"')
+                .trim_start_matches('#')
+                .trim_end_matches('/');
+            if !tag.is_empty() {
+                let start = offset + tag_item.find(tag).unwrap_or(0);
+                items.push((start, tag));
+            }
+            offset += tag_item.len() + 1;
+        }
+        items
     }
 }
 
@@ -82,4 +264,136 @@ mod tests {
         assert!(parsed_tags.is_some(), "{:?}", parsed_tags);
         assert_eq!(parsed_tags.unwrap(), test_tags);
     }
+
+    #[test]
+    fn test_parse_front_matter() {
+        let test_string = "---\ntags:\n  - one\n  - two\n---\n\ntags: [ignored]\n#three";
+        let test_tags: HashSet<String> =
+            HashSet::from(["one".to_string(), "two".to_string(), "three".to_string()]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+
+        let test_string = "---\ntags: one, two\n---";
+        let test_tags: HashSet<String> = HashSet::from(["one".to_string(), "two".to_string()]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+    }
+
+    #[test]
+    fn test_locate() {
+        let test_string = "---\ntags: [one, two]\n---\n\nSome #three and `#code`";
+        let markdown = Markdown::parse(test_string);
+        let located = Tags::locate(&markdown);
+
+        assert_eq!(located.len(), 3);
+        for tag in &located {
+            assert_eq!(&test_string[tag.span.range()], tag.value);
+        }
+        assert_eq!((located[1].span.line, located[1].span.column), (2, 13));
+        assert_eq!((located[2].span.line, located[2].span.column), (5, 7));
+
+        let test_string = r#"tags: ["test", "test2"] #tag2"#;
+        let markdown = Markdown::parse(test_string);
+        for tag in Tags::locate(&markdown) {
+            assert_eq!(&test_string[tag.span.range()], tag.value);
+        }
+    }
+
+    #[test]
+    fn test_locate_front_matter_key() {
+        let test_strings = [
+            "---\ndescription: tags for scribe\nsubtags: [scribe]\ntags:\n  - scribe\n  - notes\n---\n",
+            "---\ndescription: tags for scribe\ntags: [notes, scribe]\n---\n",
+            "---\ntags: scribe, notes\ntitle: scribe\n---\n",
+            "+++\ndescription = \"scribe\"\ntags = [\"notes\", \"scribe\"]\n+++\n",
+            "{\"description\": \"scribe\", \"tags\": [\"scribe/app\", \"scribe\"]}\n",
+        ];
+        for test_string in test_strings {
+            let markdown = Markdown::parse(test_string);
+            let located = Tags::locate(&markdown);
+            assert!(!located.is_empty(), "{}", test_string);
+            for tag in &located {
+                assert_eq!(&test_string[tag.span.range()], tag.value, "{}", test_string);
+                let line = test_string.lines().nth(tag.span.line - 1).unwrap();
+                assert!(tag.span.start > test_string.rfind("tags").unwrap());
+                assert!(!line.starts_with("title"), "{}", test_string);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_skips_code() {
+        let test_string =
+            "#tag1\n\n```c\n#include <stdio.h>\n```\n\n`#define` <!-- #hidden --> $#math$";
+        let test_tags: HashSet<String> = HashSet::from(["tag1".to_string()]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let test_string =
+            "---\ntags: [project/scribe, area/]\n---\n\n#project/scribe/backend and #todo/ #a//b";
+        let test_tags: HashSet<String> = HashSet::from([
+            "project/scribe".to_string(),
+            "area".to_string(),
+            "project/scribe/backend".to_string(),
+            "todo".to_string(),
+            "a".to_string(),
+        ]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+    }
+
+    #[test]
+    fn test_ancestors_and_is_under() {
+        assert_eq!(
+            Tags::ancestors("project/scribe/backend"),
+            vec!["project", "project/scribe", "project/scribe/backend"]
+        );
+        assert_eq!(Tags::ancestors("solo"), vec!["solo"]);
+
+        assert!(Tags::is_under("project/scribe/backend", "project/scribe"));
+        assert!(Tags::is_under("project/scribe", "#project/scribe/"));
+        assert!(!Tags::is_under("project/scribes", "project/scribe"));
+        assert!(!Tags::is_under("project", "project/scribe"));
+    }
+
+    #[test]
+    fn test_parse_unicode() {
+        let test_string =
+            "---\ntags: [日本語/会議, \"Gro\u{308}ße\"]\n---\n\n#über #größe/straße #Ελληνικά '#nicht' [[メモ#見出し]]";
+        let test_tags: HashSet<String> = HashSet::from([
+            "日本語/会議".to_string(),
+            "Größe".to_string(),
+            "über".to_string(),
+            "größe/straße".to_string(),
+            "Ελληνικά".to_string(),
+        ]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+        assert!(Tags::is_under("größe/straße", "gro\u{308}ße"));
+    }
+
+    #[test]
+    fn test_parse_skips_links() {
+        assert_eq!(Tags::parse("See [[#Heading]] and [[note#Part]]"), None);
+        assert_eq!(Tags::parse("See [the #draft](note.md)"), None);
+
+        let test_string = "[[#Heading]] #kept [link #text](https://example.com)";
+        let test_tags: HashSet<String> = HashSet::from(["kept".to_string()]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+        assert_eq!(
+            Tags::hashtags("[[#Heading]] #kept"),
+            vec!["kept".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_numbers() {
+        assert_eq!(Tags::parse("Fixed in #123 and #2026/10"), None);
+
+        let test_string = "#y2026 #123abc #1-on-1";
+        let test_tags: HashSet<String> = HashSet::from([
+            "y2026".to_string(),
+            "123abc".to_string(),
+            "1-on-1".to_string(),
+        ]);
+        assert_eq!(Tags::parse(test_string), Some(test_tags));
+    }
 }
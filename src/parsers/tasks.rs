@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::markdown::Markdown;
+use crate::parsers::span::Span;
+use crate::parsers::tags::Tags;
+
+lazy_static! {
+    static ref DUE_DATE: Regex = Regex::new(r"(?:📅|\bdue::?)\s*(\d{4}-\d{2}-\d{2})").unwrap();
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Task {
+    pub text: String,
+    pub completed: bool,
+    pub depth: usize,
+    pub tags: Vec<String>,
+    pub due: Option<NaiveDate>,
+    pub span: Span,
+}
+
+impl Task {
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due.is_some_and(|due| due < today)
+    }
+}
+
+pub struct Tasks {}
+
+impl Tasks {
+    pub fn parse(data: &str) -> Option<Vec<Task>> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<Vec<Task>> {
+        // Tags are found in the prose, so code and link text in a task are skipped
+        let located = Tags::locate(markdown);
+        let mut tasks: Vec<Task> = vec![];
+        for task in markdown.tasks() {
+            let mut tags: Vec<String> = vec![];
+            for tag in located
+                .iter()
+                .filter(|tag| task.range.contains(&tag.span.start))
+            {
+                if !tags.contains(&tag.value) {
+                    tags.push(tag.value.clone());
+                }
+            }
+            tasks.push(Task {
+                text: task.text.clone(),
+                completed: task.checked,
+                depth: task.depth,
+                tags,
+                due: Self::parse_due(&task.text),
+                span: markdown.span(task.range.clone()),
+            });
+        }
+
+        if tasks.is_empty() {
+            return None;
+        }
+
+        Some(tasks)
+    }
+
+    fn parse_due(text: &str) -> Option<NaiveDate> {
+        let cap = DUE_DATE.captures(text).ok()??;
+        NaiveDate::parse_from_str(cap.get(1)?.as_str(), "%Y-%m-%d").ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "# Todo\n\n- [ ] Write docs #docs `#code` [[#Todo]] 📅 2026-10-20\n  - [x] Outline `api` due:2026-10-01\n- plain item\n  - [ ] Nested under plain #docs #review\n\n```\n- [ ] not a task\n```";
+        let tasks = Tasks::parse(test_data).unwrap();
+
+        assert_eq!(tasks.len(), 3);
+
+        assert_eq!(
+            tasks[0].text,
+            "Write docs #docs #code [[#Todo]] 📅 2026-10-20"
+        );
+        assert!(!tasks[0].completed);
+        assert_eq!(tasks[0].depth, 0);
+        assert_eq!(tasks[0].tags, vec!["docs".to_string()]);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(tasks[0].span.line, 3);
+
+        assert_eq!(tasks[1].text, "Outline api due:2026-10-01");
+        assert!(tasks[1].completed);
+        assert_eq!(tasks[1].depth, 1);
+        assert_eq!(tasks[1].due, NaiveDate::from_ymd_opt(2026, 10, 1));
+
+        assert_eq!(tasks[2].depth, 1);
+        assert_eq!(
+            tasks[2].tags,
+            vec!["docs".to_string(), "review".to_string()]
+        );
+        assert!(tasks[2].due.is_none());
+    }
+
+    #[test]
+    fn test_is_overdue() {
+        let tasks = Tasks::parse("- [ ] late due:2026-10-01\n- [x] done due:2026-10-01").unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert!(tasks[0].is_overdue(today));
+        assert!(!tasks[1].is_overdue(today));
+        assert!(!tasks[0].is_overdue(NaiveDate::from_ymd_opt(2026, 9, 30).unwrap()));
+    }
+}
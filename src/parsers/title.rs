@@ -1,39 +1,42 @@
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
 
 pub struct Title {}
 
 impl Title {
     pub fn parse(data: &str) -> Option<String> {
+        Self::from_markdown(&Markdown::parse(data))
+    }
+
+    pub fn from_markdown(markdown: &Markdown) -> Option<String> {
         lazy_static! {
-            static ref TITLE_1: Regex = Regex::new("title: ([A-Za-z0-9\\s]+)").unwrap();
-            static ref TITLE_2: Regex = Regex::new("(?<!\\#)\\#{1} (.+)").unwrap();
+            static ref TITLE_1: Regex = Regex::new(r"(?m)^title:\s+([^\n]+)").unwrap();
         };
 
         // Default to Front Matter Title
-        let matches = Parser::get_matches(&TITLE_1, data);
-
-        let title: String;
-        if matches.is_some() {
-            let found_matches = matches.unwrap();
-            if found_matches.len() > 0 {
-                title = found_matches.into_iter().next().unwrap().trim().to_string();
-                return Some(title);
+        if markdown.front_matter_block.is_some() {
+            let title = markdown
+                .front_matter
+                .as_ref()
+                .and_then(|front_matter| front_matter.get("title"))
+                .and_then(|title| title.as_string());
+            if let Some(title) = title {
+                return Some(title.trim().to_string());
             }
+        } else if let Some(title) = Parser::locate_prose_matches(&TITLE_1, markdown).first() {
+            // Without front matter, a line starting `title:` stands in for it
+            return Some(title.value.clone());
         }
 
-        // Get Title from # header
-        let matches = Parser::get_matches(&TITLE_2, data);
-        if matches.is_some() {
-            let found_matches = matches.unwrap();
-            if found_matches.len() > 0 {
-                title = found_matches.into_iter().next().unwrap().trim().to_string();
-                return Some(title);
-            }
-        }
-        return None;
+        // Get Title from first # header
+        markdown
+            .headings()
+            .iter()
+            .find(|heading| heading.level == 1 && !heading.text.is_empty())
+            .map(|heading| heading.text.clone())
     }
 }
 
@@ -52,5 +55,35 @@ mod tests {
         let parsed_title2 = Title::parse(test_string2);
         assert!(parsed_title2.is_some());
         assert_eq!(parsed_title2.unwrap(), "This is the title".to_string());
+
+        let test_string3 = "---\ntags: [one]\n---\n\n# This is the title\ntitle: Not the title";
+        let parsed_title3 = Title::parse(test_string3);
+        assert_eq!(parsed_title3, Some("This is the title".to_string()));
+
+        let test_string4 = "```\n# Not the title\n```\n\n# This is the title\n\n# Second title";
+        let parsed_title4 = Title::parse(test_string4);
+        assert_eq!(parsed_title4, Some("This is the title".to_string()));
+
+        let test_string5 = "title: Café — Q3 plan\n\nBody";
+        let parsed_title5 = Title::parse(test_string5);
+        assert_eq!(parsed_title5, Some("Café — Q3 plan".to_string()));
+
+        let test_string6 = "# 会議メモ — 第3四半期";
+        let parsed_title6 = Title::parse(test_string6);
+        assert_eq!(parsed_title6, Some("会議メモ — 第3四半期".to_string()));
+
+        let test_string7 = "subtitle: Not the title
+
+A title: neither
+
+# This is the title";
+        let parsed_title7 = Title::parse(test_string7);
+        assert_eq!(parsed_title7, Some("This is the title".to_string()));
+
+        let test_string8 = "title: First
+
+title: Second";
+        let parsed_title8 = Title::parse(test_string8);
+        assert_eq!(parsed_title8, Some("First".to_string()));
     }
 }
@@ -1,45 +1,112 @@
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+use crate::parsers::markdown::Markdown;
 use crate::parsers::parser::Parser;
+use crate::parsers::span::Located;
 use std::collections::HashSet;
 
 pub struct WebLinks {}
 
 impl WebLinks {
     pub fn parse(data: &str) -> Option<HashSet<String>> {
-        lazy_static! {
-            static ref WEB_LINKS_1: Regex = Regex::new("(http[s]?:[^\\s\\)\\]]+)").unwrap();
-            static ref WEB_LINKS_2: Regex = Regex::new("(www.[^\\s\\)\\]]+)").unwrap();
-        };
-
-        // Search for http... links
-        let matches = Parser::get_matches(&WEB_LINKS_1, data);
+        Self::from_markdown(&Markdown::parse(data))
+    }
 
-        // Search for www.links
-        let matches_2 = Parser::get_matches(&WEB_LINKS_2, data);
+    pub fn from_markdown(markdown: &Markdown) -> Option<HashSet<String>> {
+        let located = Self::locate(markdown);
 
         let mut full_matches: HashSet<String> = HashSet::new();
-        if matches.is_some() {
-            for match_ in matches.unwrap() {
-                full_matches.insert(match_);
+        for link in &located {
+            if link.value.starts_with("http") {
+                full_matches.insert(link.value.clone());
             }
         }
 
-        if matches_2.is_some() {
-            for match_ in matches_2.unwrap() {
-                if !full_matches.contains(&format!("https://{}", match_))
-                    & !full_matches.contains(&format!("http://{}", match_))
-                {
-                    full_matches.insert(match_);
-                }
+        // Only keep www.links that don't appear as full urls elsewhere
+        for link in located {
+            if !full_matches.contains(&format!("https://{}", link.value))
+                & !full_matches.contains(&format!("http://{}", link.value))
+            {
+                full_matches.insert(link.value);
             }
         }
 
-        if full_matches.len() == 0 {
+        if full_matches.is_empty() {
             return None;
         }
 
-        return Some(full_matches);
+        Some(full_matches)
+    }
+
+    pub fn locate(markdown: &Markdown) -> Vec<Located<String>> {
+        lazy_static! {
+            static ref WEB_LINKS_1: Regex = Regex::new("(http[s]?:[^\\s\\)\\]]+)").unwrap();
+            static ref WEB_LINKS_2: Regex = Regex::new("(www.[^\\s\\)\\]]+)").unwrap();
+        };
+
+        // Search for markdown links and autolinks
+        let mut located: Vec<Located<String>> = vec![];
+        for link in markdown.links() {
+            if !link.is_image && (link.dest.starts_with("http") || link.dest.starts_with("www.")) {
+                let span = markdown.span(link.range.clone());
+                located.push(Located::new(link.dest.clone(), span));
+            }
+        }
+
+        // Search for http... links
+        let matches = Parser::locate_prose_matches(&WEB_LINKS_1, markdown);
+
+        // Search for www.links, skipping those inside an http... link
+        let matches_2 = Parser::locate_prose_matches(&WEB_LINKS_2, markdown);
+        let overlaps = |link: &Located<String>| {
+            matches
+                .iter()
+                .any(|full| full.span.start <= link.span.start && link.span.end <= full.span.end)
+        };
+        let matches_2: Vec<Located<String>> = matches_2
+            .into_iter()
+            .filter(|link| !overlaps(link))
+            .collect();
+
+        located.extend(matches);
+        located.extend(matches_2);
+        located.sort_by_key(|link| link.span.start);
+        located
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let test_data = "http://www.google.com\n[bing](https://www.bing.com) www.google.com www.rust-lang.org\n\n`https://www.code.com`";
+        let test_links: HashSet<String> = HashSet::from([
+            "http://www.google.com".to_string(),
+            "https://www.bing.com".to_string(),
+            "www.rust-lang.org".to_string(),
+        ]);
+
+        let parsed_links = WebLinks::parse(test_data);
+        assert_eq!(parsed_links, Some(test_links));
+    }
+
+    #[test]
+    fn test_locate() {
+        let test_data = "http://www.google.com\n[bing](https://www.bing.com) www.google.com";
+        let located = WebLinks::locate(&Markdown::parse(test_data));
+
+        let values: Vec<&str> = located.iter().map(|link| link.value.as_str()).collect();
+        assert_eq!(
+            values,
+            vec![
+                "http://www.google.com",
+                "https://www.bing.com",
+                "www.google.com"
+            ]
+        );
+        assert_eq!((located[2].span.line, located[2].span.column), (2, 30));
     }
 }
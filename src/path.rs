@@ -1,17 +1,18 @@
 use crate::config::ScribeConfig;
+use chrono::{DateTime, Local};
 use std::fs::rename;
 use std::io::{self, Write};
 use std::{fs, path::PathBuf};
 use walkdir::WalkDir;
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct ScribePath {
     pub path: String,
 }
 
 impl ScribePath {
     pub fn new(category: &str, base: &str) -> Self {
-        let cfg: ScribeConfig = ScribeConfig::load();
+        let cfg = ScribeConfig::global();
         let dir: String = cfg.get("directory").unwrap().to_string();
         let path = format!(
             "{}/{}/{}",
@@ -28,14 +29,14 @@ impl ScribePath {
     }
 
     pub fn root() -> Self {
-        let cfg: ScribeConfig = ScribeConfig::load();
+        let cfg = ScribeConfig::global();
         return Self {
             path: cfg.get("directory").unwrap().to_string(),
         };
     }
 
     fn get_relative(path: &str) -> String {
-        let cfg: ScribeConfig = ScribeConfig::load();
+        let cfg = ScribeConfig::global();
         let dir: String = cfg.get("directory").unwrap().to_string();
         if path.contains(&dir) {
             return path.replace(&dir, "").trim_start_matches("/").to_string();
@@ -44,7 +45,7 @@ impl ScribePath {
     }
 
     fn get_absolute(path: &str) -> String {
-        let cfg: ScribeConfig = ScribeConfig::load();
+        let cfg = ScribeConfig::global();
         let dir: String = cfg.get("directory").unwrap().to_string();
         if !path.starts_with(&dir) {
             return format!("{}/{}", &dir, path.trim_start_matches("/"));
@@ -59,6 +60,7 @@ impl ScribePath {
     pub fn get_children(&self) -> Vec<ScribePath> {
         let mut paths: Vec<ScribePath> = vec![];
         for entry in WalkDir::new(self.as_string(true))
+            .sort_by_file_name()
             .into_iter()
             .filter_map(|e| e.ok())
         {
@@ -159,12 +161,26 @@ impl ScribePath {
         return false;
     }
 
+    // Any file besides a note, one whose name has an extension other than `.md`
+    pub fn is_attachment(&self) -> bool {
+        let name = self
+            .as_pathbuf()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        match name.as_deref().and_then(|name| name.rsplit_once('.')) {
+            Some((stem, extension)) => {
+                !stem.is_empty() && !extension.is_empty() && !extension.eq_ignore_ascii_case("md")
+            }
+            None => false,
+        }
+    }
+
     pub fn is_template(&self) -> bool {
         return self.get_parent().as_string(false).starts_with("templates");
     }
 
     pub fn is_tmp(&self) -> bool {
-        return self.as_string(true).contains("tmp");
+        return self.as_string(false).split('/').next() == Some("tmp");
     }
 
     pub fn get_parent(&self) -> Self {
@@ -232,6 +248,17 @@ impl ScribePath {
         return None;
     }
 
+    pub fn modified(&self) -> Option<DateTime<Local>> {
+        let modified = fs::metadata(self.as_string(true)).ok()?.modified().ok()?;
+        Some(DateTime::from(modified))
+    }
+
+    // Not every filesystem records creation time
+    pub fn created(&self) -> Option<DateTime<Local>> {
+        let created = fs::metadata(self.as_string(true)).ok()?.created().ok()?;
+        Some(DateTime::from(created))
+    }
+
     pub fn replace(&self, replace_str: &str, new_str: &str) -> std::io::Result<()> {
         let og_data = self.get_data().unwrap();
         let new_data = og_data.replace(&replace_str, &new_str);
@@ -322,6 +349,25 @@ mod tests {
         assert!(!path2.is_valid());
     }
 
+    #[test]
+    fn test_path_is_attachment() {
+        assert!(ScribePath::new("assets", "diagram.PNG").is_attachment());
+        assert!(ScribePath::new("assets", "drawing.excalidraw").is_attachment());
+        assert!(!ScribePath::new("inbox", "note.md").is_attachment());
+        assert!(!ScribePath::new("inbox", "README").is_attachment());
+        assert!(ScribePath::new("data", "table.csv").is_attachment());
+        assert!(!ScribePath::new("", ".gitignore").is_attachment());
+    }
+
+    #[test]
+    fn test_path_is_tmp() {
+        assert!(ScribePath::new("tmp", "scratch.md").is_tmp());
+        assert!(!ScribePath::new("projects", "tmpl_notes.md").is_tmp());
+        assert!(!ScribePath::new("inbox", "tmp.md").is_tmp());
+        assert!(!ScribePath::new("tmpl", "daily.md").is_tmp());
+        assert!(!ScribePath::from("tmp-notes.md").is_tmp());
+    }
+
     #[test]
     fn test_path_is_dir() {
         let mut root = ScribePath::root();
@@ -410,10 +456,10 @@ mod tests {
     #[test]
     fn test_path_create_rename_and_delete_file() {
         let mut root = ScribePath::root();
-        root.extend("tmp/test3.md");
+        root.extend("tmp/test_rename.md");
 
         let mut new_file = ScribePath::root();
-        new_file.extend("tmp/test2_renamed.md");
+        new_file.extend("tmp/test_renamed.md");
 
         let res = root.create_file("this is test data");
         assert!(res.is_ok());
@@ -428,7 +474,7 @@ mod tests {
     #[test]
     fn test_path_get_data() {
         let mut root = ScribePath::root();
-        root.extend("tmp/test3.md");
+        root.extend("tmp/test_get_data.md");
 
         let test_data = "This is a test file".to_string();
         let res = root.create_file(&test_data);
@@ -445,7 +491,7 @@ mod tests {
     #[test]
     fn test_path_replace() {
         let mut root = ScribePath::root();
-        root.extend("tmp/test4.md");
+        root.extend("tmp/test_replace.md");
 
         let test_data = "This is a test file".to_string();
         let res = root.create_file(&test_data);
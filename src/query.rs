@@ -0,0 +1,559 @@
+use crate::index::{NoteInfo, ScribeIndex};
+use crate::parsers::date::Date;
+use crate::parsers::parser::Parser;
+use crate::parsers::tags::Tags;
+use crate::path::ScribePath;
+use crate::resolver::Resolution;
+use crate::search::SearchQuery;
+use chrono::{Duration, Local, NaiveDate};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+const FILTERS: [&str; 11] = [
+    "tag", "path", "title", "date", "created", "modified", "links-to", "field", "has", "sort",
+    "limit",
+];
+const SORT_KEYS: [&str; 6] = ["path", "title", "date", "created", "modified", "relevance"];
+
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    UnclosedQuote { position: usize },
+    MissingValue { filter: String, position: usize },
+    InvalidDate { value: String, position: usize },
+    InvalidField { value: String, position: usize },
+    InvalidSort { value: String, position: usize },
+    InvalidLimit { value: String, position: usize },
+}
+
+impl std::error::Error for QueryError {}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryError::UnclosedQuote { position } => {
+                write!(f, "Unclosed quote starting at {}", position)
+            }
+            QueryError::MissingValue { filter, position } => {
+                write!(f, "Missing value for `{}:` at {}", filter, position)
+            }
+            QueryError::InvalidDate { value, position } => write!(
+                f,
+                "Invalid date `{}` at {}, expected a date like 2026-10-01, today, yesterday or -7d, with an optional >, >=, < or <=",
+                value, position
+            ),
+            QueryError::InvalidField { value, position } => write!(
+                f,
+                "Invalid field `{}` at {}, expected key=value",
+                value, position
+            ),
+            QueryError::InvalidSort { value, position } => write!(
+                f,
+                "Invalid sort `{}` at {}, expected one of: {} (prefix with - to reverse)",
+                value,
+                position,
+                SORT_KEYS.join(", ")
+            ),
+            QueryError::InvalidLimit { value, position } => write!(
+                f,
+                "Invalid limit `{}` at {}, expected a positive number",
+                value, position
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateField {
+    Date,
+    Created,
+    Modified,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    Before,
+    BeforeOrEqual,
+    After,
+    AfterOrEqual,
+}
+
+impl Comparison {
+    fn matches(&self, date: NaiveDate, bound: NaiveDate) -> bool {
+        match self {
+            Comparison::Equal => date == bound,
+            Comparison::Before => date < bound,
+            Comparison::BeforeOrEqual => date <= bound,
+            Comparison::After => date > bound,
+            Comparison::AfterOrEqual => date >= bound,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    Tag(String),
+    Path(String),
+    Title(String),
+    Date(DateField, Comparison, NaiveDate),
+    LinksTo(String),
+    Field(String, String),
+    Has(String),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn matches(&self, note: &NoteInfo) -> bool {
+        match self {
+            Filter::Tag(tag) => note
+                .tags
+                .iter()
+                .flatten()
+                .any(|note_tag| Tags::is_under(note_tag, tag)),
+            // Whole folder or note names, so `inbox` doesn't match `inbox2/`
+            Filter::Path(prefix) => {
+                let relative = Parser::normalize(&ScribePath::from(&note.path).as_string(false));
+                prefix.is_empty()
+                    || relative == *prefix
+                    || relative.strip_suffix(".md") == Some(prefix.as_str())
+                    || relative
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            Filter::Title(text) => note
+                .title
+                .as_ref()
+                .is_some_and(|title| title.to_lowercase().contains(text.as_str())),
+            Filter::Date(field, comparison, bound) => {
+                let date = match field {
+                    DateField::Date => note.date,
+                    DateField::Created => note.created_at(),
+                    DateField::Modified => note.modified_at(),
+                };
+                date.is_some_and(|date| comparison.matches(date.date_naive(), *bound))
+            }
+            Filter::LinksTo(target) => note.has_backlink(&ScribePath::from(target)),
+            Filter::Field(key, value) => note
+                .get_field(key)
+                .is_some_and(|field| field.matches(value)),
+            Filter::Has(key) => note.get_field(key).is_some(),
+            Filter::Not(filter) => !filter.matches(note),
+        }
+    }
+
+    // Link targets are found like a wiki link from the vault root, the closest of
+    // several candidates is taken
+    fn resolve(&self, index: &ScribeIndex) -> Filter {
+        match self {
+            Filter::LinksTo(target) => match index.resolve_link(&ScribePath::root(), target) {
+                Resolution::Note(path) => Filter::LinksTo(path),
+                Resolution::Ambiguous(paths) => Filter::LinksTo(paths[0].clone()),
+                Resolution::File(_) | Resolution::Missing => self.clone(),
+            },
+            Filter::Not(filter) => Filter::Not(Box::new(filter.resolve(index))),
+            filter => filter.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Path,
+    Title,
+    Date,
+    Created,
+    Modified,
+    Relevance,
+}
+
+// e.g. `tag:meeting path:inbox/ date:>=2026-10-01 links-to:projects/scribe "free text"`
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Query {
+    pub filters: Vec<Filter>,
+    pub text: Vec<String>,
+    pub sort: Option<(SortKey, bool)>,
+    pub limit: Option<usize>,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        Self::parse_at(query, Local::now().date_naive())
+    }
+
+    // Relative dates such as `today` or `-7d` are taken from `today`
+    pub fn parse_at(query: &str, today: NaiveDate) -> Result<Self, QueryError> {
+        let mut parsed = Query::default();
+        for (position, word, quoted) in Self::split(query)? {
+            // Only a known filter name makes a filter, so `10:30` or a url stays text
+            let (negated, unnegated) = match word.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, word.as_str()),
+            };
+            let filter = unnegated
+                .split_once(':')
+                .filter(|(key, _)| !quoted && FILTERS.contains(&key.to_lowercase().as_str()));
+            let Some((key, value)) = filter else {
+                parsed.text.push(word);
+                continue;
+            };
+
+            let key = key.to_lowercase();
+            if value.is_empty() {
+                return Err(QueryError::MissingValue {
+                    filter: key,
+                    position,
+                });
+            }
+            let filter = match key.as_str() {
+                "tag" => Filter::Tag(value.trim_start_matches('#').to_string()),
+                "path" => Filter::Path(Parser::normalize(value.trim_matches('/'))),
+                "title" => Filter::Title(value.to_lowercase()),
+                "date" => Self::date_filter(DateField::Date, value, position, today)?,
+                "created" => Self::date_filter(DateField::Created, value, position, today)?,
+                "modified" => Self::date_filter(DateField::Modified, value, position, today)?,
+                "links-to" => Filter::LinksTo(value.to_string()),
+                "has" => Filter::Has(value.to_string()),
+                "field" => match value.split_once('=') {
+                    Some((field, expected)) if !field.is_empty() => {
+                        Filter::Field(field.to_string(), expected.to_string())
+                    }
+                    _ => {
+                        return Err(QueryError::InvalidField {
+                            value: value.to_string(),
+                            position,
+                        })
+                    }
+                },
+                "sort" => {
+                    parsed.sort = Some(Self::sort_key(value, position)?);
+                    continue;
+                }
+                "limit" => {
+                    let limit = value.parse::<usize>().ok().filter(|limit| *limit > 0);
+                    parsed.limit = Some(limit.ok_or(QueryError::InvalidLimit {
+                        value: value.to_string(),
+                        position,
+                    })?);
+                    continue;
+                }
+                _ => unreachable!("`{}` is in FILTERS", key),
+            };
+            parsed.filters.push(match negated {
+                true => Filter::Not(Box::new(filter)),
+                false => filter,
+            });
+        }
+        Ok(parsed)
+    }
+
+    // Whitespace separated words with their offsets and whether they open with a quote,
+    // quotes group words and are removed
+    fn split(query: &str) -> Result<Vec<(usize, String, bool)>, QueryError> {
+        let mut words: Vec<(usize, String, bool)> = vec![];
+        let mut chars = query.char_indices().peekable();
+        while let Some(&(start, first)) = chars.peek() {
+            let mut word = String::new();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    c if c.is_whitespace() => break,
+                    '"' => {
+                        let mut closed = false;
+                        for (_, quoted) in chars.by_ref() {
+                            if quoted == '"' {
+                                closed = true;
+                                break;
+                            }
+                            word.push(quoted);
+                        }
+                        if !closed {
+                            return Err(QueryError::UnclosedQuote { position: i });
+                        }
+                    }
+                    c => word.push(c),
+                }
+            }
+            if !word.is_empty() {
+                words.push((start, word, first == '"'));
+            }
+        }
+        Ok(words)
+    }
+
+    fn date_filter(
+        field: DateField,
+        value: &str,
+        position: usize,
+        today: NaiveDate,
+    ) -> Result<Filter, QueryError> {
+        let (comparison, date) = [
+            (">=", Comparison::AfterOrEqual),
+            ("<=", Comparison::BeforeOrEqual),
+            (">", Comparison::After),
+            ("<", Comparison::Before),
+            ("=", Comparison::Equal),
+        ]
+        .iter()
+        .find_map(|(operator, comparison)| {
+            value.strip_prefix(operator).map(|date| (*comparison, date))
+        })
+        .unwrap_or_else(|| match value.starts_with('-') {
+            // A relative date such as `-7d` means since then
+            true => (Comparison::AfterOrEqual, value),
+            false => (Comparison::Equal, value),
+        });
+
+        match Self::day(date, today) {
+            Some(day) => Ok(Filter::Date(field, comparison, day)),
+            None => Err(QueryError::InvalidDate {
+                value: value.to_string(),
+                position,
+            }),
+        }
+    }
+
+    fn day(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+        match value {
+            "today" => return Some(today),
+            "yesterday" => return Some(today - Duration::days(1)),
+            _ => {}
+        }
+
+        // Relative offsets like -7d or -2w
+        if let Some(offset) = value.strip_prefix('-') {
+            let (count, unit) = offset.split_at(offset.len().saturating_sub(1));
+            let count = count.parse::<i64>().ok()?;
+            let days = match unit {
+                "d" => count,
+                "w" => count * 7,
+                _ => return None,
+            };
+            return Some(today - Duration::days(days));
+        }
+        Date::parse_day(value)
+    }
+
+    fn sort_key(value: &str, position: usize) -> Result<(SortKey, bool), QueryError> {
+        let (descending, name) = match value.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, value),
+        };
+        let key = match name.to_lowercase().as_str() {
+            "path" => SortKey::Path,
+            "title" => SortKey::Title,
+            "date" => SortKey::Date,
+            "created" => SortKey::Created,
+            "modified" => SortKey::Modified,
+            "relevance" => SortKey::Relevance,
+            _ => {
+                return Err(QueryError::InvalidSort {
+                    value: value.to_string(),
+                    position,
+                })
+            }
+        };
+        Ok((key, descending))
+    }
+
+    // Matching notes, ranked by relevance when there is free text and by path otherwise
+    pub fn evaluate(&self, index: &ScribeIndex) -> Vec<ScribePath> {
+        let relevance: Option<HashMap<String, f64>> = match self.text.is_empty() {
+            true => None,
+            false => {
+                let text = self
+                    .text
+                    .iter()
+                    .map(|text| match text.contains(char::is_whitespace) {
+                        true => format!("\"{}\"", text),
+                        false => text.clone(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                // Text made only of stopwords doesn't narrow the results
+                let query = SearchQuery::parse(&text);
+                match query.is_empty() {
+                    true => None,
+                    false => Some(index.search_index.search(&query).into_iter().collect()),
+                }
+            }
+        };
+
+        let filters: Vec<Filter> = self
+            .filters
+            .iter()
+            .map(|filter| filter.resolve(index))
+            .collect();
+        let mut notes: Vec<&NoteInfo> = index
+            .notes
+            .iter()
+            .filter(|note| filters.iter().all(|filter| filter.matches(note)))
+            .filter(|note| {
+                relevance
+                    .as_ref()
+                    .is_none_or(|scores| scores.contains_key(&note.path))
+            })
+            .collect();
+
+        let default = match relevance {
+            Some(_) => (SortKey::Relevance, false),
+            None => (SortKey::Path, false),
+        };
+        let (key, descending) = self.sort.unwrap_or(default);
+        notes.sort_by(|a, b| {
+            let ordering = match key {
+                SortKey::Path => Ordering::Equal,
+                SortKey::Title => a.title.cmp(&b.title),
+                SortKey::Date => a.date.cmp(&b.date),
+                SortKey::Created => a.created_at().cmp(&b.created_at()),
+                SortKey::Modified => a.modified_at().cmp(&b.modified_at()),
+                // Best matches first, so descending reverses to worst first
+                SortKey::Relevance => {
+                    let score = |note: &NoteInfo| {
+                        relevance
+                            .as_ref()
+                            .and_then(|scores| scores.get(&note.path).copied())
+                            .unwrap_or(0.0)
+                    };
+                    score(b).total_cmp(&score(a))
+                }
+            };
+            let ordering = ordering.then_with(|| a.path.cmp(&b.path));
+            match descending {
+                true => ordering.reverse(),
+                false => ordering,
+            }
+        });
+
+        notes
+            .into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|note| ScribePath::from(&note.path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_query_parse() {
+        let today = day("2026-10-18");
+        let query = Query::parse_at(
+            "tag:#meeting path:/inbox/ date:>=2026-10-01 modified:-7d links-to:projects/scribe -has:draft \"free text\" loose sort:-date limit:5",
+            today,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query.filters,
+            vec![
+                Filter::Tag("meeting".to_string()),
+                Filter::Path("inbox".to_string()),
+                Filter::Date(DateField::Date, Comparison::AfterOrEqual, day("2026-10-01")),
+                Filter::Date(
+                    DateField::Modified,
+                    Comparison::AfterOrEqual,
+                    day("2026-10-11")
+                ),
+                Filter::LinksTo("projects/scribe".to_string()),
+                Filter::Not(Box::new(Filter::Has("draft".to_string()))),
+            ]
+        );
+        assert_eq!(query.text, vec!["free text", "loose"]);
+        assert_eq!(query.sort, Some((SortKey::Date, true)));
+        assert_eq!(query.limit, Some(5));
+
+        let quoted =
+            Query::parse_at("path:\"my notes/\" title:Weekly \"re: notes\"", today).unwrap();
+        assert_eq!(
+            quoted.filters,
+            vec![
+                Filter::Path("my notes".to_string()),
+                Filter::Title("weekly".to_string()),
+            ]
+        );
+        assert_eq!(quoted.text, vec!["re: notes"]);
+    }
+
+    #[test]
+    fn test_query_relative_dates() {
+        let today = day("2026-10-18");
+        let filter = |query: &str| Query::parse_at(query, today).unwrap().filters.remove(0);
+
+        let since = filter("modified:-7d");
+        assert_eq!(
+            since,
+            Filter::Date(
+                DateField::Modified,
+                Comparison::AfterOrEqual,
+                day("2026-10-11")
+            )
+        );
+        assert!(
+            matches!(since, Filter::Date(_, comparison, bound) if comparison.matches(today, bound))
+        );
+        assert_eq!(
+            filter("modified:<-2w"),
+            Filter::Date(DateField::Modified, Comparison::Before, day("2026-10-04"))
+        );
+        assert_eq!(
+            filter("date:2026-10-11"),
+            Filter::Date(DateField::Date, Comparison::Equal, day("2026-10-11"))
+        );
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let error = |query: &str| Query::parse(query).unwrap_err();
+
+        assert_eq!(
+            error("tag: x"),
+            QueryError::MissingValue {
+                filter: "tag".to_string(),
+                position: 0
+            }
+        );
+        assert_eq!(
+            error("tag:a \"open"),
+            QueryError::UnclosedQuote { position: 6 }
+        );
+        assert!(matches!(
+            error("date:>=soon"),
+            QueryError::InvalidDate { .. }
+        ));
+        assert!(matches!(error("sort:size"), QueryError::InvalidSort { .. }));
+        assert!(matches!(error("limit:0"), QueryError::InvalidLimit { .. }));
+        assert!(matches!(
+            error("field:status"),
+            QueryError::InvalidField { .. }
+        ));
+
+        let message = error("sort:size").to_string();
+        assert!(message.contains("Invalid sort `size` at 0"));
+    }
+
+    #[test]
+    fn test_query_text_with_colons() {
+        let query = Query::parse("http://example.com meet 10:30 -re:notes colour:red").unwrap();
+        assert!(query.filters.is_empty());
+        assert_eq!(
+            query.text,
+            vec![
+                "http://example.com",
+                "meet",
+                "10:30",
+                "-re:notes",
+                "colour:red"
+            ]
+        );
+
+        let query = Query::parse("TAG:meeting https://example.com/tag:x").unwrap();
+        assert_eq!(query.filters, vec![Filter::Tag("meeting".to_string())]);
+        assert_eq!(query.text, vec!["https://example.com/tag:x"]);
+    }
+}
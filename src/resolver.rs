@@ -0,0 +1,751 @@
+use crate::index::NoteInfo;
+use crate::parsers::parser::Parser;
+use crate::parsers::span::Span;
+use crate::path::ScribePath;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkKind {
+    Wiki,
+    Markdown,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Resolution {
+    // The absolute path of the linked note
+    Note(String),
+    // The absolute path of the linked file, for targets with an extension other than `.md`
+    File(String),
+    // Every note the link could mean, closest first
+    Ambiguous(Vec<String>),
+    Missing,
+}
+
+impl Resolution {
+    pub fn note(&self) -> Option<&str> {
+        match self {
+            Resolution::Note(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+// A wiki or markdown link as written, with the note it points at
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ResolvedLink {
+    pub kind: LinkKind,
+    pub target: String,
+    pub heading: Option<String>,
+    pub block_id: Option<String>,
+    pub span: Span,
+    pub resolution: Resolution,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkProblem {
+    MissingNote,
+    MissingHeading,
+    MissingBlock,
+}
+
+// A link pointing at nothing, as reported by `ScribeIndex::broken_links`
+#[derive(Clone, PartialEq, Debug)]
+pub struct BrokenLink<'a> {
+    pub source: ScribePath,
+    pub link: &'a ResolvedLink,
+    pub problem: LinkProblem,
+}
+
+// Formatted as `path:line:column: message`, as compilers report errors
+impl fmt::Display for BrokenLink<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let span = self.link.span;
+        write!(
+            f,
+            "{}:{}:{}: ",
+            self.source.as_string(false),
+            span.line,
+            span.column
+        )?;
+        match self.problem {
+            LinkProblem::MissingNote if ScribePath::from(&self.link.target).is_attachment() => {
+                write!(f, "no note or file matches `{}`", self.link.target)
+            }
+            LinkProblem::MissingNote => write!(f, "no note matches `{}`", self.link.target),
+            LinkProblem::MissingHeading if self.link.target.is_empty() => write!(
+                f,
+                "no heading `{}` in this note",
+                self.link.heading.as_deref().unwrap_or_default()
+            ),
+            LinkProblem::MissingHeading => write!(
+                f,
+                "no heading `{}` in `{}`",
+                self.link.heading.as_deref().unwrap_or_default(),
+                self.link.target
+            ),
+            LinkProblem::MissingBlock => write!(
+                f,
+                "no block `^{}` in `{}`",
+                self.link.block_id.as_deref().unwrap_or_default(),
+                self.link.target
+            ),
+        }
+    }
+}
+
+// Every file besides notes in the vault, by relative path and by file name. Kept on the index
+// and changed one file at a time, so links to a file can be re-resolved when it comes or goes.
+#[derive(PartialEq, Debug, Default)]
+pub struct AttachmentFiles {
+    by_path: BTreeMap<String, ScribePath>,
+    by_name: HashMap<String, BTreeSet<String>>,
+}
+
+impl AttachmentFiles {
+    pub fn new() -> Self {
+        let mut files = AttachmentFiles::default();
+        for file in ScribePath::root().get_children() {
+            if !file.is_tmp() && !file.is_hidden() && file.as_pathbuf().is_file() {
+                files.insert(&file);
+            }
+        }
+        files
+    }
+
+    // Adds a file, returning its lowercase name, the key links to it are tracked under
+    pub fn insert(&mut self, file: &ScribePath) -> BTreeSet<String> {
+        let relative = Parser::normalize(&file.as_string(false));
+        if !file.is_attachment() || self.by_path.contains_key(&relative) {
+            return BTreeSet::new();
+        }
+        let name = Self::name(&relative);
+        self.by_name
+            .entry(name.clone())
+            .or_default()
+            .insert(relative.clone());
+        self.by_path.insert(relative, file.clone());
+        BTreeSet::from([name.to_lowercase()])
+    }
+
+    // Drops a file, returning its lowercase name as `insert` does
+    pub fn remove(&mut self, file: &ScribePath) -> BTreeSet<String> {
+        let relative = Parser::normalize(&file.as_string(false));
+        if self.by_path.remove(&relative).is_none() {
+            return BTreeSet::new();
+        }
+        let name = Self::name(&relative);
+        if let Some(paths) = self.by_name.get_mut(&name) {
+            paths.remove(&relative);
+            if paths.is_empty() {
+                self.by_name.remove(&name);
+            }
+        }
+        BTreeSet::from([name.to_lowercase()])
+    }
+
+    // Lists the vault again, returning the names of files added or removed since
+    pub fn refresh(&mut self) -> BTreeSet<String> {
+        let current = Self::new();
+        let mut changed: BTreeSet<String> = BTreeSet::new();
+        let removed: Vec<ScribePath> = self
+            .by_path
+            .iter()
+            .filter(|(relative, _)| !current.by_path.contains_key(*relative))
+            .map(|(_, file)| file.clone())
+            .collect();
+        for file in removed {
+            changed.extend(self.remove(&file));
+        }
+        for file in current.by_path.values() {
+            changed.extend(self.insert(file));
+        }
+        changed
+    }
+
+    // Sorted by relative path
+    pub fn files(&self) -> impl Iterator<Item = &ScribePath> {
+        self.by_path.values()
+    }
+
+    fn name(relative: &str) -> String {
+        relative.rsplit('/').next().unwrap_or_default().to_string()
+    }
+
+    // References are relative to the note at `source` or the vault, or a bare file name
+    pub fn resolve(&self, source: &str, target: &str) -> Option<&ScribePath> {
+        let folder = ScribePath::from(source).get_parent().as_string(false);
+        let candidates = [
+            LinkResolver::join_relative(&folder, target),
+            LinkResolver::join_relative("", target),
+        ];
+        candidates
+            .iter()
+            .flatten()
+            .find_map(|candidate| self.by_path.get(candidate))
+            .or_else(|| {
+                Some(target)
+                    .filter(|target| !target.contains('/'))
+                    .and_then(|target| self.by_name.get(target))
+                    .and_then(|paths| paths.first())
+                    .and_then(|relative| self.by_path.get(relative))
+            })
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Entry {
+    path: String,
+    // Relative to the vault, without the `.md` extension
+    relative: String,
+    folder: String,
+    aliases: Vec<String>,
+}
+
+impl Entry {
+    fn new(note: &NoteInfo) -> Self {
+        let relative = Parser::normalize(&ScribePath::from(&note.path).as_string(false));
+        let relative = LinkResolver::strip_extension(&relative).to_string();
+        let folder = match relative.rsplit_once('/') {
+            Some((folder, _)) => folder.to_string(),
+            None => String::new(),
+        };
+        let aliases: Vec<String> = note
+            .aliases()
+            .into_iter()
+            .map(|alias| Parser::normalize(alias.trim()))
+            .filter(|alias| !alias.is_empty())
+            .collect();
+        Entry {
+            path: note.path.clone(),
+            relative,
+            folder,
+            aliases,
+        }
+    }
+
+    fn name(&self) -> String {
+        let name = self.relative.rsplit('/').next().unwrap_or_default();
+        name.to_lowercase()
+    }
+
+    fn is_exact(&self, wanted: &str) -> bool {
+        self.relative == wanted
+            || self.relative.ends_with(&format!("/{}", wanted))
+            || self.aliases.iter().any(|alias| alias == wanted)
+    }
+}
+
+// Maps link targets to notes the way Obsidian does, by path, shortest unique name or alias.
+// Notes are added and removed one at a time, and the resolver remembers which names each
+// note's links were looked up under, so a change only re-resolves the links it can affect.
+#[derive(PartialEq, Debug, Default)]
+pub struct LinkResolver {
+    entries: HashMap<String, Entry>,
+    by_name: HashMap<String, Vec<String>>,
+    by_alias: HashMap<String, Vec<String>>,
+    // The lowercase names and aliases each note's links use, and the notes using each one
+    lookups: HashMap<String, BTreeSet<String>>,
+    linked_by: HashMap<String, BTreeSet<String>>,
+}
+
+impl LinkResolver {
+    pub fn new(notes: &[NoteInfo]) -> Self {
+        let mut resolver = LinkResolver::default();
+        for note in notes {
+            resolver.insert(note);
+        }
+        resolver
+    }
+
+    // Adds or replaces the note, returning the names and aliases whose notes changed
+    pub fn insert(&mut self, note: &NoteInfo) -> BTreeSet<String> {
+        let entry = Entry::new(note);
+        if self.entries.get(&note.path) == Some(&entry) {
+            return BTreeSet::new();
+        }
+        let old = self.remove_entry(&note.path);
+        let changed = Self::changed_keys(old.as_ref(), Some(&entry));
+
+        self.by_name
+            .entry(entry.name())
+            .or_default()
+            .push(entry.path.clone());
+        for alias in &entry.aliases {
+            self.by_alias
+                .entry(alias.to_lowercase())
+                .or_default()
+                .push(entry.path.clone());
+        }
+        self.entries.insert(entry.path.clone(), entry);
+        changed
+    }
+
+    // Drops the note and what its links used, returning the names and aliases it held
+    pub fn remove(&mut self, path: &str) -> BTreeSet<String> {
+        self.track(path, &[]);
+        let old = self.remove_entry(path);
+        Self::changed_keys(old.as_ref(), None)
+    }
+
+    fn remove_entry(&mut self, path: &str) -> Option<Entry> {
+        let entry = self.entries.remove(path)?;
+        let unlist = |map: &mut HashMap<String, Vec<String>>, key: String| {
+            if let Some(paths) = map.get_mut(&key) {
+                paths.retain(|other| other != path);
+                if paths.is_empty() {
+                    map.remove(&key);
+                }
+            }
+        };
+        unlist(&mut self.by_name, entry.name());
+        for alias in &entry.aliases {
+            unlist(&mut self.by_alias, alias.to_lowercase());
+        }
+        Some(entry)
+    }
+
+    // Names whose notes differ between two versions of an entry, compared case sensitively as
+    // an exact case match is preferred
+    fn changed_keys(old: Option<&Entry>, new: Option<&Entry>) -> BTreeSet<String> {
+        let names = |entry: Option<&Entry>| -> BTreeSet<(String, String)> {
+            let Some(entry) = entry else {
+                return BTreeSet::new();
+            };
+            let mut names = BTreeSet::from([(entry.name(), entry.relative.clone())]);
+            for alias in &entry.aliases {
+                names.insert((alias.to_lowercase(), alias.clone()));
+            }
+            names
+        };
+        names(old)
+            .symmetric_difference(&names(new))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    // Records the names and aliases the links of `source` were looked up under
+    pub fn track(&mut self, source: &str, links: &[ResolvedLink]) {
+        let keys: BTreeSet<String> = links
+            .iter()
+            .flat_map(|link| Self::lookup_keys(source, link.kind, &link.target))
+            .collect();
+        for key in self.lookups.remove(source).into_iter().flatten() {
+            if let Some(sources) = self.linked_by.get_mut(&key) {
+                sources.remove(source);
+                if sources.is_empty() {
+                    self.linked_by.remove(&key);
+                }
+            }
+        }
+        for key in &keys {
+            self.linked_by
+                .entry(key.clone())
+                .or_default()
+                .insert(source.to_string());
+        }
+        if !keys.is_empty() {
+            self.lookups.insert(source.to_string(), keys);
+        }
+    }
+
+    // Notes with a link looked up under any of `keys`
+    pub fn linking(&self, keys: &BTreeSet<String>) -> BTreeSet<String> {
+        keys.iter()
+            .filter_map(|key| self.linked_by.get(key))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    // The `by_name` and `by_alias` keys `resolve` reads for `target`
+    fn lookup_keys(source: &str, kind: LinkKind, target: &str) -> Vec<String> {
+        let target = Parser::normalize(Self::strip_extension(target.trim()));
+        if target.is_empty() {
+            return vec![];
+        }
+        let source_relative = Parser::normalize(&ScribePath::from(source).as_string(false));
+        let folder = match source_relative.rsplit_once('/') {
+            Some((folder, _)) => folder,
+            None => "",
+        };
+
+        let mut paths = vec![target.clone()];
+        if kind == LinkKind::Markdown || target.starts_with(['.', '/']) {
+            paths.extend(Self::join_relative(folder, &target));
+            paths.extend(Self::join_relative("", &target));
+        }
+        let mut keys: Vec<String> = paths
+            .iter()
+            .map(|path| path.rsplit('/').next().unwrap_or_default().to_lowercase())
+            .collect();
+        keys.push(target.to_lowercase());
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    // Joins a relative reference onto a folder, resolving `.` and `..`
+    pub fn join_relative(folder: &str, target: &str) -> Option<String> {
+        let mut parts: Vec<&str> = match target.starts_with('/') {
+            true => vec![],
+            false => folder.split('/').filter(|part| !part.is_empty()).collect(),
+        };
+        for part in target.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop()?;
+                }
+                part => parts.push(part),
+            }
+        }
+        Some(parts.join("/"))
+    }
+
+    fn strip_extension(target: &str) -> &str {
+        match target.len() >= 3 && target.is_char_boundary(target.len() - 3) {
+            true if target[target.len() - 3..].eq_ignore_ascii_case(".md") => {
+                &target[..target.len() - 3]
+            }
+            _ => target,
+        }
+    }
+
+    // Resolves `target` as written in the note at `source`
+    pub fn resolve(&self, source: &str, kind: LinkKind, target: &str) -> Resolution {
+        let target = Parser::normalize(Self::strip_extension(target.trim()));
+        if target.is_empty() {
+            return Resolution::Note(source.to_string());
+        }
+        let source_relative = Parser::normalize(&ScribePath::from(source).as_string(false));
+        let folder = match source_relative.rsplit_once('/') {
+            Some((folder, _)) => folder,
+            None => "",
+        };
+
+        // Markdown links and explicit paths are relative to the note, then to the vault
+        let explicit =
+            target.starts_with("./") || target.starts_with("../") || target.starts_with('/');
+        if kind == LinkKind::Markdown || explicit {
+            let candidates = [
+                Self::join_relative(folder, &target),
+                Self::join_relative("", &target),
+            ];
+            for candidate in candidates.iter().flatten() {
+                let found = self.by_path(candidate);
+                if !found.is_empty() {
+                    return self.choose(found, candidate, folder);
+                }
+            }
+            if explicit {
+                return Resolution::Missing;
+            }
+        }
+
+        let found = self.by_suffix(&target);
+        if !found.is_empty() {
+            return self.choose(found, &target, folder);
+        }
+        let found = self.named(&self.by_alias, &target.to_lowercase());
+        self.choose(found, &target, folder)
+    }
+
+    fn named<'a>(&'a self, map: &HashMap<String, Vec<String>>, key: &str) -> Vec<&'a Entry> {
+        map.get(key)
+            .into_iter()
+            .flatten()
+            .filter_map(|path| self.entries.get(path))
+            .collect()
+    }
+
+    // Notes whose relative path is `path`, ignoring case
+    fn by_path(&self, path: &str) -> Vec<&Entry> {
+        let path = path.to_lowercase();
+        let name = path.rsplit('/').next().unwrap_or_default();
+        let mut found = self.named(&self.by_name, name);
+        found.retain(|entry| entry.relative.to_lowercase() == path);
+        found
+    }
+
+    // Notes whose relative path ends with `target`, a bare name matches in any folder
+    fn by_suffix(&self, target: &str) -> Vec<&Entry> {
+        let target = target.to_lowercase();
+        let name = target.rsplit('/').next().unwrap_or_default();
+        let suffix = format!("/{}", target);
+        let mut found = self.named(&self.by_name, name);
+        found.retain(|entry| {
+            let relative = entry.relative.to_lowercase();
+            relative == target || relative.ends_with(&suffix)
+        });
+        found
+    }
+
+    // Prefers an exact case match, then a note beside the source, otherwise reports every candidate
+    fn choose(&self, mut found: Vec<&Entry>, wanted: &str, folder: &str) -> Resolution {
+        if found.len() > 1 {
+            let exact: Vec<&Entry> = found
+                .iter()
+                .copied()
+                .filter(|entry| entry.is_exact(wanted))
+                .collect();
+            if !exact.is_empty() {
+                found = exact;
+            }
+        }
+        if found.len() > 1 {
+            let near: Vec<&Entry> = found
+                .iter()
+                .copied()
+                .filter(|entry| entry.folder == folder)
+                .collect();
+            if near.len() == 1 {
+                found = near;
+            }
+        }
+
+        match found.len() {
+            0 => Resolution::Missing,
+            1 => Resolution::Note(found[0].path.clone()),
+            _ => {
+                found.sort_by_key(|entry| (entry.relative.len(), entry.relative.clone()));
+                Resolution::Ambiguous(found.into_iter().map(|entry| entry.path.clone()).collect())
+            }
+        }
+    }
+
+    // Every wiki and markdown link in the note, same note anchors resolve to the note itself
+    pub fn resolve_note(
+        &self,
+        note: &NoteInfo,
+        files: &AttachmentFiles,
+    ) -> Option<Vec<ResolvedLink>> {
+        let spans = note.spans.as_ref()?;
+        let mut links: Vec<ResolvedLink> = vec![];
+        for located in &spans.embedded_links {
+            let link = &located.value;
+            links.push(ResolvedLink {
+                kind: LinkKind::Wiki,
+                target: link.target.clone(),
+                heading: link.heading.clone(),
+                block_id: link.block_id.clone(),
+                span: located.span,
+                resolution: self.resolve_target(files, &note.path, LinkKind::Wiki, &link.target),
+            });
+        }
+        for located in &spans.internal_links {
+            let (target, fragment) = match located.value.split_once('#') {
+                Some((target, fragment)) => (target, Some(fragment)),
+                None => (located.value.as_str(), None),
+            };
+            let fragment = fragment.filter(|fragment| !fragment.is_empty());
+            let block_id = fragment.and_then(|fragment| fragment.strip_prefix('^'));
+            let heading = fragment.filter(|_| block_id.is_none());
+            links.push(ResolvedLink {
+                kind: LinkKind::Markdown,
+                target: target.to_string(),
+                heading: heading.map(|heading| heading.to_string()),
+                block_id: block_id.map(|block_id| block_id.to_string()),
+                span: located.span,
+                resolution: self.resolve_target(files, &note.path, LinkKind::Markdown, target),
+            });
+        }
+
+        links.sort_by_key(|link| link.span.start);
+        Some(links).filter(|links| !links.is_empty())
+    }
+
+    // Targets with an extension other than `.md` are files when one exists, notes otherwise
+    fn resolve_target(
+        &self,
+        files: &AttachmentFiles,
+        source: &str,
+        kind: LinkKind,
+        target: &str,
+    ) -> Resolution {
+        if ScribePath::from(target).is_attachment() {
+            let target = Parser::normalize(target.trim());
+            if let Some(file) = files.resolve(source, &target) {
+                return Resolution::File(file.as_string(true));
+            }
+        }
+        self.resolve(source, kind, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::front_matter::FrontMatter;
+
+    fn note(relative: &str, aliases: &[&str]) -> NoteInfo {
+        let mut note = NoteInfo::parse(&ScribePath::from(relative));
+        if !aliases.is_empty() {
+            let yaml = format!("aliases: [{}]", aliases.join(", "));
+            note.frontmatter = FrontMatter::from_yaml(&yaml);
+        }
+        note
+    }
+
+    fn resolve(notes: &[NoteInfo], source: &str, kind: LinkKind, target: &str) -> Resolution {
+        LinkResolver::new(notes).resolve(&ScribePath::from(source).as_string(true), kind, target)
+    }
+
+    fn found(relative: &str) -> Resolution {
+        Resolution::Note(ScribePath::from(relative).as_string(true))
+    }
+
+    #[test]
+    fn test_resolve_wiki_links() {
+        let notes = vec![
+            note("projects/test/file.md", &[]),
+            note("projects/Plan.md", &["Roadmap"]),
+            note("inbox/plan.md", &[]),
+            note("inbox/today.md", &[]),
+        ];
+
+        // Shortest unique basename, with or without the extension and in any case
+        let source = "inbox/today.md";
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "file"),
+            found("projects/test/file.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "File.md"),
+            found("projects/test/file.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "test/file"),
+            found("projects/test/file.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "other/file"),
+            Resolution::Missing
+        );
+
+        // Exact case wins, then the note beside the source
+        assert_eq!(
+            resolve(&notes, "projects/test/file.md", LinkKind::Wiki, "Plan"),
+            found("projects/Plan.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "PLAN"),
+            found("inbox/plan.md")
+        );
+        assert_eq!(
+            resolve(&notes, "projects/test/file.md", LinkKind::Wiki, "PLAN"),
+            Resolution::Ambiguous(vec![
+                ScribePath::from("inbox/plan.md").as_string(true),
+                ScribePath::from("projects/Plan.md").as_string(true),
+            ])
+        );
+
+        // Aliases and links to a heading in the same note
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Wiki, "roadmap"),
+            found("projects/Plan.md")
+        );
+        assert_eq!(resolve(&notes, source, LinkKind::Wiki, ""), found(source));
+    }
+
+    #[test]
+    fn test_resolve_markdown_links() {
+        let notes = vec![
+            note("projects/test/file.md", &[]),
+            note("projects/x.md", &[]),
+            note("inbox/today.md", &[]),
+        ];
+
+        let source = "projects/test/file.md";
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "../x.md"),
+            found("projects/x.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "./../x"),
+            found("projects/x.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "/inbox/today.md"),
+            found("inbox/today.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "inbox/today.md"),
+            found("inbox/today.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "today.md"),
+            found("inbox/today.md")
+        );
+        assert_eq!(
+            resolve(&notes, source, LinkKind::Markdown, "../../../x.md"),
+            Resolution::Missing
+        );
+    }
+
+    #[test]
+    fn test_resolver_changes() {
+        let mut resolver = LinkResolver::new(&[note("projects/Plan.md", &[])]);
+        let keys = |keys: &[&str]| -> BTreeSet<String> {
+            keys.iter().map(|key| key.to_string()).collect()
+        };
+
+        // Only names and aliases a change adds or removes are reported
+        let plan = note("projects/Plan.md", &["Roadmap"]);
+        assert_eq!(resolver.insert(&plan), keys(&["roadmap"]));
+        assert!(resolver.insert(&plan).is_empty());
+        assert_eq!(
+            resolver.insert(&note("inbox/plan.md", &[])),
+            keys(&["plan"])
+        );
+
+        let source = note("inbox/today.md", &[]);
+        resolver.insert(&source);
+        let link = |kind: LinkKind, target: &str| ResolvedLink {
+            kind,
+            target: target.to_string(),
+            heading: None,
+            block_id: None,
+            span: Span {
+                start: 0,
+                end: 0,
+                line: 1,
+                column: 1,
+            },
+            resolution: Resolution::Missing,
+        };
+        resolver.track(
+            &source.path,
+            &[
+                link(LinkKind::Wiki, "Roadmap"),
+                link(LinkKind::Markdown, "../projects/Plan.md"),
+            ],
+        );
+        let linking = BTreeSet::from([source.path.clone()]);
+        assert_eq!(resolver.linking(&keys(&["roadmap"])), linking);
+        assert_eq!(resolver.linking(&keys(&["plan"])), linking);
+        assert!(resolver.linking(&keys(&["today"])).is_empty());
+
+        assert_eq!(resolver.remove(&plan.path), keys(&["plan", "roadmap"]));
+        assert_eq!(
+            resolver.resolve(&source.path, LinkKind::Wiki, "plan"),
+            found("inbox/plan.md")
+        );
+        resolver.remove(&source.path);
+        assert!(resolver.linking(&keys(&["roadmap"])).is_empty());
+    }
+
+    #[test]
+    fn test_join_relative() {
+        assert_eq!(
+            LinkResolver::join_relative("a/b", "../c"),
+            Some("a/c".to_string())
+        );
+        assert_eq!(
+            LinkResolver::join_relative("a/b", "/c"),
+            Some("c".to_string())
+        );
+        assert_eq!(LinkResolver::join_relative("a", "../../c"), None);
+    }
+}
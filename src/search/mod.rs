@@ -0,0 +1,322 @@
+pub mod snippet;
+pub mod tokenizer;
+
+use crate::parsers::markdown::Markdown;
+use crate::path::ScribePath;
+use serde::{Deserialize, Serialize};
+use snippet::Snippet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use tokenizer::Tokenizer;
+
+// BM25 parameters, the usual defaults
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+// The terms of a single note and where they occur
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Document {
+    pub length: u32,
+    pub positions: BTreeMap<String, Vec<u32>>,
+}
+
+impl Document {
+    pub fn from_text(text: &str) -> Self {
+        let mut document = Document::default();
+        for token in Tokenizer::tokenize(text) {
+            document.length += 1;
+            document
+                .positions
+                .entry(token.term)
+                .or_default()
+                .push(token.position);
+        }
+        document
+    }
+
+    // Only the prose and link text of a note is searched, front matter, code and link
+    // destinations are not. A title given in front matter is searched too, one taken from a
+    // heading already is.
+    pub fn from_markdown(markdown: &Markdown, title: Option<&str>) -> Self {
+        let mut parts: Vec<(usize, &str)> = markdown.prose().collect();
+        parts.extend(
+            markdown
+                .links()
+                .iter()
+                .map(|link| (link.range.start, link.text.as_str())),
+        );
+        parts.sort_by_key(|(offset, _)| *offset);
+
+        let mut text = String::new();
+        if let Some(title) = title.filter(|title| {
+            !markdown
+                .headings()
+                .iter()
+                .any(|heading| heading.text == *title)
+        }) {
+            text.push_str(title);
+            text.push('\n');
+        }
+        for (_, part) in parts {
+            text.push_str(part);
+            text.push('\n');
+        }
+        Self::from_text(&text)
+    }
+
+    fn has_phrase(&self, phrase: &[(u32, String)]) -> bool {
+        let Some((first_offset, first)) = phrase.first() else {
+            return true;
+        };
+        let Some(starts) = self.positions.get(first) else {
+            return false;
+        };
+        starts.iter().any(|start| {
+            phrase.iter().skip(1).all(|(offset, term)| {
+                let position = start + offset - first_offset;
+                self.positions
+                    .get(term)
+                    .is_some_and(|positions| positions.binary_search(&position).is_ok())
+            })
+        })
+    }
+}
+
+// Free words rank results, quoted phrases must appear as written
+#[derive(PartialEq, Debug, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<String>,
+    // Terms with their offset from the start of the phrase, stopwords leave gaps
+    pub phrases: Vec<Vec<(u32, String)>>,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = SearchQuery::default();
+        for (i, part) in query.split('"').enumerate() {
+            let tokens = Tokenizer::tokenize(part);
+            // Odd parts sit between quotes, an unclosed quote runs to the end
+            if i % 2 == 1 && tokens.len() > 1 {
+                let phrase = tokens
+                    .into_iter()
+                    .map(|token| (token.position, token.term))
+                    .collect();
+                parsed.phrases.push(phrase);
+            } else {
+                parsed
+                    .terms
+                    .extend(tokens.into_iter().map(|token| token.term));
+            }
+        }
+        parsed
+    }
+
+    pub fn all_terms(&self) -> HashSet<String> {
+        let mut terms: HashSet<String> = self.terms.iter().cloned().collect();
+        for phrase in &self.phrases {
+            terms.extend(phrase.iter().map(|(_, term)| term.clone()));
+        }
+        terms
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchResult {
+    pub path: ScribePath,
+    pub score: f64,
+    pub snippet: Snippet,
+}
+
+// An inverted index over note bodies, keyed by absolute path
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    documents: HashMap<String, Document>,
+    postings: HashMap<String, BTreeSet<String>>,
+    total_length: u64,
+}
+
+impl SearchIndex {
+    pub fn insert(&mut self, path: &str, text: &str) {
+        self.add(path, Document::from_text(text));
+    }
+
+    pub fn add(&mut self, path: &str, document: Document) {
+        self.remove(path);
+        for term in document.positions.keys() {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(path.to_string());
+        }
+        self.total_length += document.length as u64;
+        self.documents.insert(path.to_string(), document);
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        let Some(document) = self.documents.remove(path) else {
+            return;
+        };
+        for term in document.positions.keys() {
+            if let Some(paths) = self.postings.get_mut(term) {
+                paths.remove(path);
+                if paths.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_length -= document.length as u64;
+    }
+
+    // Each note's terms by path, saved with the index so loading doesn't re-read every note
+    pub fn documents(&self) -> &HashMap<String, Document> {
+        &self.documents
+    }
+
+    pub fn document(&self, path: &str) -> Option<&Document> {
+        self.documents.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    // Ranked paths, best first, ties broken by path so results are stable
+    pub fn search(&self, query: &SearchQuery) -> Vec<(String, f64)> {
+        let terms = query.all_terms();
+        let mut candidates: BTreeSet<&String> = BTreeSet::new();
+        for term in &terms {
+            candidates.extend(self.postings.get(term).into_iter().flatten());
+        }
+
+        let mut results: Vec<(String, f64)> = vec![];
+        for path in candidates {
+            let document = &self.documents[path];
+            if !query
+                .phrases
+                .iter()
+                .all(|phrase| document.has_phrase(phrase))
+            {
+                continue;
+            }
+            let score = terms.iter().map(|term| self.score(document, term)).sum();
+            results.push((path.clone(), score));
+        }
+
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        results
+    }
+
+    fn score(&self, document: &Document, term: &str) -> f64 {
+        let Some(positions) = document.positions.get(term) else {
+            return 0.0;
+        };
+        let count = self.documents.len() as f64;
+        let matching = self.postings.get(term).map_or(0, |paths| paths.len()) as f64;
+        let idf = (1.0 + (count - matching + 0.5) / (matching + 0.5)).ln();
+
+        let average = self.total_length as f64 / count;
+        let frequency = positions.len() as f64;
+        let norm = 1.0 - B + B * document.length as f64 / average;
+        idf * frequency * (K1 + 1.0) / (frequency + K1 * norm)
+    }
+}
+
+// Terms are derived from the files, so indexes covering the same notes are equal
+impl PartialEq for SearchIndex {
+    fn eq(&self, other: &Self) -> bool {
+        self.documents.len() == other.documents.len()
+            && self
+                .documents
+                .keys()
+                .all(|path| other.documents.contains_key(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_index() -> SearchIndex {
+        let mut index = SearchIndex::default();
+        index.insert(
+            "a.md",
+            "The quarterly budget review is due. Cuts, budget everywhere and more budget.",
+        );
+        index.insert("b.md", "Notes on the review of the budget for the garden.");
+        index.insert("c.md", "Gardening ideas: tomatoes and running beans.");
+        index
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let index = test_index();
+        let results = index.search(&SearchQuery::parse("budget"));
+        let paths: Vec<&str> = results.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, vec!["a.md", "b.md"]);
+        assert!(results[0].1 > results[1].1);
+
+        // Stemming matches other forms of a word
+        let results = index.search(&SearchQuery::parse("gardens run"));
+        let paths: Vec<&str> = results.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, vec!["c.md", "b.md"]);
+
+        assert!(index.search(&SearchQuery::parse("the of")).is_empty());
+    }
+
+    #[test]
+    fn test_search_phrase() {
+        let index = test_index();
+        let query = SearchQuery::parse("\"budget review\"");
+        assert_eq!(query.phrases.len(), 1);
+
+        let results = index.search(&query);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "a.md");
+
+        // Stopwords inside a phrase still have to line up
+        let results = index.search(&SearchQuery::parse("\"review of the budget\""));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "b.md");
+        assert!(index
+            .search(&SearchQuery::parse("\"review the budget\""))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_remove() {
+        let mut index = test_index();
+        index.remove("a.md");
+        assert_eq!(index.len(), 2);
+        let results = index.search(&SearchQuery::parse("budget"));
+        assert_eq!(results.len(), 1);
+
+        index.insert("b.md", "Replaced entirely");
+        assert!(index.search(&SearchQuery::parse("budget")).is_empty());
+        assert_eq!(index.total_length, 2 + 5);
+    }
+
+    #[test]
+    fn test_document_from_markdown() {
+        let data = "---\ntitle: Quarterly Plan\nstatus: drafted\n---\n\n# Budget\n\nReview the [numbers](finance/sheet.md)\n\n```\nlet forecast = 1;\n```";
+        let markdown = Markdown::parse(data);
+        let document = Document::from_markdown(&markdown, Some("Quarterly Plan"));
+        let terms: Vec<&str> = document
+            .positions
+            .keys()
+            .map(|term| term.as_str())
+            .collect();
+        assert_eq!(terms, vec!["budget", "number", "plan", "quarter", "review"]);
+
+        // A title taken from the first heading isn't counted twice
+        let markdown = Markdown::parse("# Budget\n\nReview");
+        let document = Document::from_markdown(&markdown, Some("Budget"));
+        assert_eq!(document.length, 2);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::search::tokenizer::{Token, Tokenizer};
+use std::collections::HashSet;
+use std::ops::Range;
+
+// Words shown around the best match
+const SNIPPET_WORDS: usize = 16;
+const SNIPPET_CONTEXT: usize = 3;
+const ELLIPSIS: &str = "…";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snippet {
+    pub text: String,
+    // Byte ranges in `text` of the words that matched the query
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl Snippet {
+    // Picks the window of the note covering the most distinct query terms
+    pub fn from_text(data: &str, terms: &HashSet<String>) -> Self {
+        let tokens = Tokenizer::tokenize(data);
+        if tokens.is_empty() {
+            return Snippet {
+                text: String::new(),
+                highlights: vec![],
+            };
+        }
+
+        let matched = |token: &Token| terms.contains(&token.term);
+        let mut best = (0, 0);
+        for (i, token) in tokens.iter().enumerate() {
+            if !matched(token) {
+                continue;
+            }
+            let window = &tokens[i..(i + SNIPPET_WORDS).min(tokens.len())];
+            let found: HashSet<&str> = window
+                .iter()
+                .filter(|token| matched(token))
+                .map(|token| token.term.as_str())
+                .collect();
+            if found.len() > best.1 {
+                best = (i, found.len());
+            }
+        }
+
+        let first = best.0.saturating_sub(SNIPPET_CONTEXT);
+        let last = (first + SNIPPET_WORDS).min(tokens.len()) - 1;
+        let range = tokens[first].range.start..tokens[last].range.end;
+
+        // Whitespace is swapped byte for byte, so token offsets stay valid
+        let mut text = data[range.clone()].replace(['\n', '\r', '\t'], " ");
+        let mut shift = 0;
+        if range.start > 0 {
+            text.insert_str(0, ELLIPSIS);
+            shift = ELLIPSIS.len();
+        }
+        if data[range.end..].chars().any(|c| c.is_alphanumeric()) {
+            text.push_str(ELLIPSIS);
+        }
+
+        let highlights = tokens[first..=last]
+            .iter()
+            .filter(|token| matched(token))
+            .map(|token| {
+                token.range.start - range.start + shift..token.range.end - range.start + shift
+            })
+            .collect();
+        Snippet { text, highlights }
+    }
+
+    // Wraps each match, e.g. `highlight("**", "**")` for markdown
+    pub fn highlight(&self, open: &str, close: &str) -> String {
+        let mut highlighted = self.text.clone();
+        for range in self.highlights.iter().rev() {
+            highlighted.insert_str(range.end, close);
+            highlighted.insert_str(range.start, open);
+        }
+        highlighted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let data = "# Meeting\n\nWe discussed the roadmap at length. Afterwards the\nbudget review for the scribe project was scheduled, and the budget approved.\n\nUnrelated closing words that go on and on for quite a while longer here.";
+        let terms: HashSet<String> = ["budget", "scribe"]
+            .into_iter()
+            .map(|term| term.to_string())
+            .collect();
+
+        let snippet = Snippet::from_text(data, &terms);
+        assert!(snippet.text.starts_with("…"));
+        assert!(snippet.text.ends_with("…"));
+        assert!(!snippet.text.contains('\n'));
+        assert_eq!(snippet.highlights.len(), 3);
+        assert!(snippet
+            .highlight("**", "**")
+            .contains("the **budget** review for the **scribe** project"));
+    }
+
+    #[test]
+    fn test_snippet_without_match() {
+        let terms: HashSet<String> = HashSet::new();
+        let snippet = Snippet::from_text("Short note", &terms);
+        assert_eq!(snippet.text, "Short note");
+        assert!(snippet.highlights.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::parsers::parser::Parser;
+use lazy_static::lazy_static;
+use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
+use std::ops::Range;
+
+const STOPWORDS: [&str; 64] = [
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "because",
+    "been", "but", "by", "can", "could", "did", "do", "does", "for", "from", "had", "has", "have",
+    "he", "her", "his", "how", "i", "if", "in", "into", "is", "it", "its", "me", "my", "no", "not",
+    "of", "on", "or", "our", "she", "so", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "we", "were", "which", "will", "with", "you",
+];
+
+lazy_static! {
+    static ref STEMMER: Stemmer = Stemmer::create(Algorithm::English);
+    static ref STOPWORD_SET: HashSet<&'static str> = STOPWORDS.into_iter().collect();
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Token {
+    pub term: String,
+    // Counts every word including stopwords, so phrases keep their gaps
+    pub position: u32,
+    pub range: Range<usize>,
+}
+
+pub struct Tokenizer {}
+
+impl Tokenizer {
+    // Stemmed, lowercased words with stopwords dropped
+    pub fn tokenize(text: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = vec![];
+        for (position, range) in Self::words(text).into_iter().enumerate() {
+            let word = Parser::normalize(&text[range.clone()].to_lowercase()).replace('’', "'");
+            if !Self::is_stopword(&word) {
+                tokens.push(Token {
+                    term: Self::stem(&word),
+                    position: position as u32,
+                    range,
+                });
+            }
+        }
+        tokens
+    }
+
+    pub fn is_stopword(word: &str) -> bool {
+        STOPWORD_SET.contains(word)
+    }
+
+    pub fn stem(word: &str) -> String {
+        STEMMER.stem(word).into_owned()
+    }
+
+    // Runs of letters and digits, apostrophes are kept between letters as in "don't"
+    fn words(text: &str) -> Vec<Range<usize>> {
+        let mut words: Vec<Range<usize>> = vec![];
+        let mut start: Option<usize> = None;
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let joins = (c == '\'' || c == '’')
+                && start.is_some()
+                && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+            if c.is_alphanumeric() || joins {
+                start.get_or_insert(i);
+            } else if let Some(begin) = start.take() {
+                words.push(begin..i);
+            }
+        }
+        if let Some(begin) = start {
+            words.push(begin..text.len());
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let text = "The Running of the Bulls, don't stop! Größe 2024";
+        let tokens = Tokenizer::tokenize(text);
+
+        let terms: Vec<&str> = tokens.iter().map(|token| token.term.as_str()).collect();
+        assert_eq!(terms, vec!["run", "bull", "don't", "stop", "größe", "2024"]);
+
+        let positions: Vec<u32> = tokens.iter().map(|token| token.position).collect();
+        assert_eq!(positions, vec![1, 4, 5, 6, 7, 8]);
+        assert_eq!(&text[tokens[1].range.clone()], "Bulls");
+    }
+
+    #[test]
+    fn test_tokenize_none() {
+        assert!(Tokenizer::tokenize("the and of -- ** ''").is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::index::NoteInfo;
+use crate::search::Document;
+use crate::storage::{IndexStorage, StorageError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    notes: &'a [NoteInfo],
+}
+
+#[derive(Deserialize)]
+struct OwnedManifest {
+    notes: Vec<NoteInfo>,
+}
+
+// The pretty-printed `.scribe` manifest, rewritten in full on every save
+pub struct JsonStorage {
+    path: PathBuf,
+}
+
+impl JsonStorage {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    // Search documents sit beside the manifest, e.g. `.scribe.search`
+    fn documents_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".search");
+        self.path.with_file_name(name)
+    }
+}
+
+impl IndexStorage for JsonStorage {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn load(&self) -> Result<Vec<NoteInfo>, StorageError> {
+        let data = std::fs::read_to_string(&self.path)?;
+        let mut manifest: OwnedManifest = serde_json::from_str(&data)?;
+        manifest.notes.iter_mut().for_each(NoteInfo::rebase);
+        Ok(manifest.notes)
+    }
+
+    fn save(&self, notes: &[NoteInfo]) -> Result<(), StorageError> {
+        let json_str = serde_json::to_string_pretty(&Manifest { notes })?;
+        std::fs::write(&self.path, json_str)?;
+        Ok(())
+    }
+
+    fn load_documents(&self) -> Result<HashMap<String, Document>, StorageError> {
+        match std::fs::read_to_string(self.documents_path()) {
+            Ok(data) => Ok(serde_json::from_str(&data)?),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save_documents(&self, documents: &HashMap<String, Document>) -> Result<(), StorageError> {
+        let sorted: BTreeMap<&String, &Document> = documents.iter().collect();
+        std::fs::write(self.documents_path(), serde_json::to_string(&sorted)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::ScribePath;
+
+    #[test]
+    fn test_json_save_and_load() {
+        let mut note = ScribePath::root();
+        note.extend("inbox/test_file1.md");
+        let notes = vec![NoteInfo::parse(&note)];
+
+        let mut location = ScribePath::root();
+        location.extend("tmp/test_json_storage.json");
+        std::fs::create_dir_all(location.get_parent().as_pathbuf()).unwrap();
+
+        let storage = JsonStorage::new(location.as_pathbuf());
+        storage.save(&notes).unwrap();
+        assert_eq!(storage.load().unwrap(), notes);
+
+        let _ = location.delete();
+        assert!(matches!(storage.load(), Err(StorageError::Io(_))));
+    }
+
+    #[test]
+    fn test_json_save_and_load_documents() {
+        let mut location = ScribePath::root();
+        location.extend("tmp/test_json_documents.json");
+        std::fs::create_dir_all(location.get_parent().as_pathbuf()).unwrap();
+
+        let storage = JsonStorage::new(location.as_pathbuf());
+        assert!(storage.load_documents().unwrap().is_empty());
+
+        let documents = HashMap::from([(
+            "notes/garden.md".to_string(),
+            Document::from_text("Planning the vegetable garden"),
+        )]);
+        storage.save_documents(&documents).unwrap();
+        assert!(storage
+            .documents_path()
+            .ends_with("tmp/test_json_documents.json.search"));
+        assert_eq!(storage.load_documents().unwrap(), documents);
+
+        let _ = std::fs::remove_file(storage.documents_path());
+    }
+}
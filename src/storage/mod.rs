@@ -0,0 +1,107 @@
+pub mod json;
+pub mod sqlite;
+
+use crate::index::NoteInfo;
+use crate::search::Document;
+use std::collections::HashMap;
+use std::fmt;
+
+// Where an index is kept between runs
+pub trait IndexStorage: Send {
+    fn name(&self) -> &str;
+    fn load(&self) -> Result<Vec<NoteInfo>, StorageError>;
+    // Replaces everything stored with `notes`, keeping their order
+    fn save(&self, notes: &[NoteInfo]) -> Result<(), StorageError>;
+
+    // Brings the storage up to date on `ScribeIndex::write`, backends with row level changes
+    // are current already and the rest are rewritten
+    fn flush(
+        &self,
+        notes: &[NoteInfo],
+        documents: &HashMap<String, Document>,
+    ) -> Result<(), StorageError> {
+        self.save(notes)?;
+        self.save_documents(documents)
+    }
+
+    // Row level changes, backends without them persist on the next save
+    fn upsert(&self, _note: &NoteInfo) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn upsert_all(&self, notes: &[NoteInfo]) -> Result<(), StorageError> {
+        notes.iter().try_for_each(|note| self.upsert(note))
+    }
+
+    fn remove(&self, _path: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    // Search documents by note path, notes without one are read and tokenized again
+    fn load_documents(&self) -> Result<HashMap<String, Document>, StorageError> {
+        Ok(HashMap::new())
+    }
+
+    fn save_documents(&self, _documents: &HashMap<String, Document>) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn upsert_document(&self, _path: &str, _document: &Document) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn upsert_documents(&self, documents: &HashMap<String, Document>) -> Result<(), StorageError> {
+        documents
+            .iter()
+            .try_for_each(|(path, document)| self.upsert_document(path, document))
+    }
+}
+
+impl PartialEq for dyn IndexStorage {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl fmt::Debug for dyn IndexStorage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IndexStorage({})", self.name())
+    }
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Sqlite(rusqlite::Error),
+}
+
+impl std::error::Error for StorageError {}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Io(err) => write!(f, "Index IO Error: {}", err),
+            StorageError::Json(err) => write!(f, "Index JSON Error: {}", err),
+            StorageError::Sqlite(err) => write!(f, "Index SQLite Error: {}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        StorageError::Json(err)
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(err: rusqlite::Error) -> Self {
+        StorageError::Sqlite(err)
+    }
+}
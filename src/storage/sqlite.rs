@@ -0,0 +1,465 @@
+use crate::index::NoteInfo;
+use crate::parsers::front_matter::FieldValue;
+use crate::path::ScribePath;
+use crate::search::Document;
+use crate::storage::json::JsonStorage;
+use crate::storage::{IndexStorage, StorageError};
+use rusqlite::{params, Connection, Params};
+use std::collections::HashMap;
+use std::path::Path;
+
+// Bumped with each schema change, older databases are upgraded on open
+const SCHEMA_VERSION: i32 = 2;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS notes (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    title TEXT,
+    date TEXT,
+    mtime INTEGER,
+    size INTEGER,
+    hash INTEGER,
+    info TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tags (
+    note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS links (
+    note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    target TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS metadata (
+    note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS documents (
+    note_id INTEGER PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+    length INTEGER NOT NULL,
+    positions TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS tags_note ON tags(note_id);
+CREATE INDEX IF NOT EXISTS tags_tag ON tags(tag);
+CREATE INDEX IF NOT EXISTS links_note ON links(note_id);
+CREATE INDEX IF NOT EXISTS links_target ON links(target);
+CREATE INDEX IF NOT EXISTS metadata_note ON metadata(note_id);
+CREATE INDEX IF NOT EXISTS metadata_key ON metadata(key, value);
+";
+
+// Notes are kept whole in `info`, tags, links and metadata are split out into indexed tables
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, StorageError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, StorageError> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let version: i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version < SCHEMA_VERSION {
+            connection.execute_batch(SCHEMA)?;
+            connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        Ok(Self { connection })
+    }
+
+    // Copies an existing `.scribe` JSON index into the database, replacing its contents
+    pub fn import_json(&self, path: impl AsRef<Path>) -> Result<usize, StorageError> {
+        let notes = JsonStorage::new(path).load()?;
+        self.save(&notes)?;
+        Ok(notes.len())
+    }
+
+    pub fn len(&self) -> Result<usize, StorageError> {
+        let count: i64 = self
+            .connection
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, StorageError> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn find_by_tag(&self, tag: &str) -> Result<Vec<ScribePath>, StorageError> {
+        self.paths(
+            "SELECT DISTINCT notes.path FROM notes JOIN tags ON tags.note_id = notes.id WHERE tags.tag = ?1 ORDER BY notes.id",
+            [tag.trim_start_matches('#')],
+        )
+    }
+
+    pub fn find_linking_to(&self, target: &str) -> Result<Vec<ScribePath>, StorageError> {
+        self.paths(
+            "SELECT DISTINCT notes.path FROM notes JOIN links ON links.note_id = notes.id WHERE links.target = ?1 ORDER BY notes.id",
+            [target],
+        )
+    }
+
+    pub fn find_by_field(&self, key: &str, value: &str) -> Result<Vec<ScribePath>, StorageError> {
+        self.paths(
+            "SELECT DISTINCT notes.path FROM notes JOIN metadata ON metadata.note_id = notes.id WHERE metadata.key = ?1 AND metadata.value = ?2 ORDER BY notes.id",
+            params![key, value],
+        )
+    }
+
+    fn paths<P: Params>(&self, query: &str, values: P) -> Result<Vec<ScribePath>, StorageError> {
+        let mut statement = self.connection.prepare_cached(query)?;
+        let rows = statement.query_map(values, |row| row.get::<_, String>(0))?;
+        let mut paths: Vec<ScribePath> = vec![];
+        for path in rows {
+            paths.push(ScribePath::from(&path?));
+        }
+        Ok(paths)
+    }
+
+    fn write_note(connection: &Connection, note: &NoteInfo) -> Result<(), StorageError> {
+        let info = serde_json::to_string(note)?;
+        let stamp = note.stamp;
+        connection.execute(
+            "INSERT INTO notes (path, title, date, mtime, size, hash, info)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(path) DO UPDATE SET
+                title = excluded.title, date = excluded.date, mtime = excluded.mtime,
+                size = excluded.size, hash = excluded.hash, info = excluded.info",
+            params![
+                note.path,
+                note.title,
+                note.date.map(|date| date.to_rfc3339()),
+                // SQLite integers are signed, the bits round trip unchanged
+                stamp.map(|stamp| stamp.mtime as i64),
+                stamp.map(|stamp| stamp.size as i64),
+                stamp.map(|stamp| stamp.hash as i64),
+                info,
+            ],
+        )?;
+        let id: i64 = connection.query_row(
+            "SELECT id FROM notes WHERE path = ?1",
+            [&note.path],
+            |row| row.get(0),
+        )?;
+
+        // Replace Child Rows
+        for table in ["tags", "links", "metadata"] {
+            connection.execute(&format!("DELETE FROM {} WHERE note_id = ?1", table), [id])?;
+        }
+
+        let mut tags: Vec<&String> = note.tags.iter().flatten().collect();
+        tags.sort();
+        for tag in tags {
+            connection.execute(
+                "INSERT INTO tags (note_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+
+        let mut links: Vec<(&str, &str)> = vec![];
+        links.extend(
+            note.embedded_links
+                .iter()
+                .flatten()
+                .map(|link| ("wiki", link.target.as_str())),
+        );
+        links.extend(
+            note.internal_links
+                .iter()
+                .flatten()
+                .map(|link| ("internal", link.as_str())),
+        );
+        links.extend(
+            note.web_links
+                .iter()
+                .flatten()
+                .map(|link| ("web", link.as_str())),
+        );
+        links.sort();
+        links.dedup();
+        for (kind, target) in links {
+            connection.execute(
+                "INSERT INTO links (note_id, kind, target) VALUES (?1, ?2, ?3)",
+                params![id, kind, target],
+            )?;
+        }
+
+        for (key, value) in note.metadata().iter() {
+            let values = match value {
+                FieldValue::List(items) => {
+                    items.iter().filter_map(|item| item.as_string()).collect()
+                }
+                _ => value.as_string().into_iter().collect::<Vec<String>>(),
+            };
+            for value in values {
+                connection.execute(
+                    "INSERT INTO metadata (note_id, key, value) VALUES (?1, ?2, ?3)",
+                    params![id, key, value],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_document(
+        connection: &Connection,
+        path: &str,
+        document: &Document,
+    ) -> Result<(), StorageError> {
+        connection.execute(
+            "INSERT INTO documents (note_id, length, positions)
+             SELECT id, ?2, ?3 FROM notes WHERE path = ?1
+             ON CONFLICT(note_id) DO UPDATE SET
+                length = excluded.length, positions = excluded.positions",
+            params![
+                path,
+                document.length,
+                serde_json::to_string(&document.positions)?
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl IndexStorage for SqliteStorage {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn load(&self) -> Result<Vec<NoteInfo>, StorageError> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT info FROM notes ORDER BY id")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        let mut notes: Vec<NoteInfo> = vec![];
+        for info in rows {
+            notes.push(serde_json::from_str(&info?)?);
+        }
+        Ok(notes)
+    }
+
+    fn save(&self, notes: &[NoteInfo]) -> Result<(), StorageError> {
+        let transaction = self.connection.unchecked_transaction()?;
+        transaction.execute("DELETE FROM notes", [])?;
+        for note in notes {
+            Self::write_note(&transaction, note)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    // Every change is written as it's made
+    fn flush(
+        &self,
+        _notes: &[NoteInfo],
+        _documents: &HashMap<String, Document>,
+    ) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn upsert(&self, note: &NoteInfo) -> Result<(), StorageError> {
+        self.upsert_all(std::slice::from_ref(note))
+    }
+
+    fn upsert_all(&self, notes: &[NoteInfo]) -> Result<(), StorageError> {
+        let transaction = self.connection.unchecked_transaction()?;
+        for note in notes {
+            Self::write_note(&transaction, note)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn remove(&self, path: &str) -> Result<(), StorageError> {
+        self.connection
+            .execute("DELETE FROM notes WHERE path = ?1", [path])?;
+        Ok(())
+    }
+
+    fn load_documents(&self) -> Result<HashMap<String, Document>, StorageError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT notes.path, documents.length, documents.positions FROM documents JOIN notes ON notes.id = documents.note_id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut documents: HashMap<String, Document> = HashMap::new();
+        for row in rows {
+            let (path, length, positions) = row?;
+            let positions = serde_json::from_str(&positions)?;
+            documents.insert(path, Document { length, positions });
+        }
+        Ok(documents)
+    }
+
+    fn save_documents(&self, documents: &HashMap<String, Document>) -> Result<(), StorageError> {
+        let transaction = self.connection.unchecked_transaction()?;
+        transaction.execute("DELETE FROM documents", [])?;
+        for (path, document) in documents {
+            Self::write_document(&transaction, path, document)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn upsert_document(&self, path: &str, document: &Document) -> Result<(), StorageError> {
+        Self::write_document(&self.connection, path, document)
+    }
+
+    fn upsert_documents(&self, documents: &HashMap<String, Document>) -> Result<(), StorageError> {
+        let transaction = self.connection.unchecked_transaction()?;
+        for (path, document) in documents {
+            Self::write_document(&transaction, path, document)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_notes() -> Vec<NoteInfo> {
+        [
+            "inbox/test_file1.md",
+            "inbox/test_file2.md",
+            "inbox/test_file4.md",
+        ]
+        .iter()
+        .map(|relative| {
+            let mut path = ScribePath::root();
+            path.extend(relative);
+            NoteInfo::parse(&path)
+        })
+        .collect()
+    }
+
+    fn count(storage: &SqliteStorage, table: &str) -> i64 {
+        storage
+            .connection
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sqlite_save_and_load() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        assert!(storage.is_empty().unwrap());
+
+        let notes = fixture_notes();
+        storage.save(&notes).unwrap();
+        assert_eq!(storage.load().unwrap(), notes);
+        assert_eq!(storage.len().unwrap(), 3);
+
+        let tagged = storage.find_by_tag("#tag1").unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].get_base(), Some("test_file1.md".to_string()));
+
+        let linking = storage.find_linking_to("test_file1").unwrap();
+        let bases: Vec<String> = linking.iter().filter_map(|path| path.get_base()).collect();
+        assert_eq!(bases, vec!["test_file2.md", "test_file4.md"]);
+
+        // Saving again replaces rather than appends
+        storage.save(&notes[..1]).unwrap();
+        assert_eq!(storage.len().unwrap(), 1);
+        assert!(storage.find_linking_to("test_file1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_upsert_and_remove() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        let mut notes = fixture_notes();
+        storage.save(&notes).unwrap();
+        let tags = count(&storage, "tags");
+
+        // Updates keep the note's position
+        notes[0].tags = Some(["renamed".to_string()].into_iter().collect());
+        storage.upsert(&notes[0]).unwrap();
+        assert_eq!(storage.load().unwrap(), notes);
+        assert_eq!(count(&storage, "tags"), tags - 1);
+        assert_eq!(storage.find_by_tag("renamed").unwrap().len(), 1);
+
+        storage.remove(&notes[1].path).unwrap();
+        notes.remove(1);
+        assert_eq!(storage.load().unwrap(), notes);
+        assert_eq!(count(&storage, "tags"), 1);
+        assert_eq!(count(&storage, "links"), 5);
+    }
+
+    #[test]
+    fn test_sqlite_flush_keeps_rows() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        let mut notes = fixture_notes();
+        storage.upsert_all(&notes).unwrap();
+        let ids = |storage: &SqliteStorage| -> Vec<i64> {
+            let mut statement = storage
+                .connection
+                .prepare("SELECT id FROM notes ORDER BY id")
+                .unwrap();
+            let rows = statement.query_map([], |row| row.get(0)).unwrap();
+            rows.map(|id| id.unwrap()).collect()
+        };
+        let before = ids(&storage);
+        assert_eq!(before.len(), 3);
+
+        // Rows are written as notes change, so a flush leaves them as they are
+        notes[1].title = Some("Changed".to_string());
+        storage.upsert(&notes[1]).unwrap();
+        storage.flush(&notes[..1], &HashMap::new()).unwrap();
+        assert_eq!(ids(&storage), before);
+        assert_eq!(storage.load().unwrap(), notes);
+    }
+
+    #[test]
+    fn test_sqlite_import_json() {
+        let mut fixture = ScribePath::root();
+        fixture.extend("test_index.json");
+
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        let imported = storage.import_json(fixture.as_pathbuf()).unwrap();
+        assert_eq!(imported, storage.len().unwrap());
+        assert_eq!(
+            storage.load().unwrap(),
+            JsonStorage::new(fixture.as_pathbuf()).load().unwrap()
+        );
+        assert!(!storage.find_by_tag("projekt/größe").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_documents() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        let notes = fixture_notes();
+        storage.save(&notes).unwrap();
+
+        let mut documents: HashMap<String, Document> = notes
+            .iter()
+            .map(|note| (note.path.clone(), Document::from_text(&note.path)))
+            .collect();
+        storage.save_documents(&documents).unwrap();
+        assert_eq!(storage.load_documents().unwrap(), documents);
+
+        let updated = Document::from_text("the budget review");
+        storage.upsert_document(&notes[0].path, &updated).unwrap();
+        documents.insert(notes[0].path.clone(), updated);
+        assert_eq!(storage.load_documents().unwrap(), documents);
+
+        // Documents go with their note
+        storage.remove(&notes[1].path).unwrap();
+        documents.remove(&notes[1].path);
+        assert_eq!(storage.load_documents().unwrap(), documents);
+        assert_eq!(count(&storage, "documents"), 2);
+    }
+}
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::{fs, str};
 
+use crate::config::ScribeConfig;
+use crate::parsers::front_matter::{FrontMatter, FrontMatterFormat};
 use crate::parsers::parser::Parser;
 use crate::path::ScribePath;
 use crate::ScribeError;
@@ -11,7 +13,46 @@ use crate::ScribeError;
 lazy_static! {
     static ref TEMPLATE_KEYS: Regex = Regex::new(r"\{\{\s([a-zA-Z0-9\_]+)\s\}\}").unwrap();
     static ref TEMPLATE_DEFAULT_FOLDER: Regex =
-        Regex::new("default_folder: ([A-Za-z0-9\\_]+)").unwrap();
+        Regex::new("\"?default_folder\"?\\s*[:=]\\s*\"?([A-Za-z0-9\\_]+)").unwrap();
+}
+
+enum TemplateField {
+    Text(&'static str, &'static str),
+    List(&'static str, &'static str),
+}
+
+impl TemplateField {
+    fn render(&self, format: FrontMatterFormat) -> String {
+        match (self, format) {
+            (TemplateField::Text(key, value), FrontMatterFormat::Yaml) => {
+                format!("{}: {}", key, value)
+            }
+            (TemplateField::List(key, value), FrontMatterFormat::Yaml) => {
+                format!("{}: [{}]", key, value)
+            }
+            (TemplateField::Text(key, value), FrontMatterFormat::Toml) => {
+                format!("{} = \"{}\"", key, value)
+            }
+            (TemplateField::List(key, value), FrontMatterFormat::Toml) => {
+                format!("{} = [{}]", key, value)
+            }
+            (TemplateField::Text(key, value), FrontMatterFormat::Json) => {
+                format!("  \"{}\": \"{}\"", key, value)
+            }
+            (TemplateField::List(key, value), FrontMatterFormat::Json) => {
+                format!("  \"{}\": [{}]", key, value)
+            }
+        }
+    }
+
+    fn render_block(fields: &[TemplateField], format: FrontMatterFormat) -> String {
+        let lines: Vec<String> = fields.iter().map(|field| field.render(format)).collect();
+        match format {
+            FrontMatterFormat::Yaml => format!("---\n{}\n---", lines.join("\n")),
+            FrontMatterFormat::Toml => format!("+++\n{}\n+++", lines.join("\n")),
+            FrontMatterFormat::Json => format!("{{\n{}\n}}", lines.join(",\n")),
+        }
+    }
 }
 
 pub struct ScribeTemplate {
@@ -43,6 +84,16 @@ impl ScribeTemplate {
     }
 
     pub fn get_default_folder(&self) -> Option<String> {
+        // Filled templates carry parseable front matter in any dialect
+        let front_matter = FrontMatter::parse(&self.data);
+        let default_folder = front_matter
+            .as_ref()
+            .and_then(|front_matter| front_matter.get("default_folder"))
+            .and_then(|default_folder| default_folder.as_string());
+        if default_folder.is_some() {
+            return default_folder;
+        }
+
         let matches = Parser::get_matches(&TEMPLATE_DEFAULT_FOLDER, &self.data);
         if matches.is_some() {
             let unwrapped = matches.unwrap();
@@ -75,29 +126,48 @@ pub struct ScribeTemplateLibrary {
 }
 
 impl ScribeTemplateLibrary {
-    fn builtins() -> HashMap<String, ScribeTemplate> {
+    fn builtins(format: FrontMatterFormat) -> HashMap<String, ScribeTemplate> {
         let mut builtin_templates: HashMap<String, ScribeTemplate> = HashMap::new();
 
         // Basic Template
+        let front_matter = TemplateField::render_block(
+            &[
+                TemplateField::Text("title", "{{ TITLE }}"),
+                TemplateField::Text("date", "{{ DATE }}"),
+                TemplateField::List("tags", "{{ TAGS }}"),
+            ],
+            format,
+        );
         builtin_templates.insert(
             "basic".to_string(),
-            ScribeTemplate::from_str(
-                "---\ntitle: {{ TITLE }}\ndate: {{ DATE }}\ntags: {{ TAGS }}\n---\n\n# {{ TITLE }}",
-            ),
+            ScribeTemplate::from_str(&format!("{}\n\n# {{{{ TITLE }}}}", front_matter)),
         );
 
         // Image Card
+        let front_matter =
+            TemplateField::render_block(&[TemplateField::Text("title", "{{ TITLE }}")], format);
         builtin_templates.insert(
             "image".to_string(),
-            ScribeTemplate::from_str(
-                r#"---\ntitle: {{ TITLE }}\n---\n\n# {{ TITLE }} \n<p align='center'>\n<img src="{{ IMAGE }}">\n</p>"#)
+            ScribeTemplate::from_str(&format!(
+                "{}\n\n# {{{{ TITLE }}}} \n<p align='center'>\n<img src=\"{{{{ IMAGE }}}}\">\n</p>",
+                front_matter
+            )),
         );
 
-        return builtin_templates;
+        builtin_templates
     }
 
     pub fn load() -> Self {
-        let mut templates: HashMap<String, ScribeTemplate> = Self::builtins();
+        let cfg: ScribeConfig = ScribeConfig::load();
+        let format = cfg
+            .get("front_matter")
+            .and_then(|format| format.parse().ok())
+            .unwrap_or(FrontMatterFormat::Yaml);
+        Self::load_with_format(format)
+    }
+
+    pub fn load_with_format(format: FrontMatterFormat) -> Self {
+        let mut templates: HashMap<String, ScribeTemplate> = Self::builtins(format);
 
         // Find User Options
         let mut template_dir_path: ScribePath = ScribePath::root();
@@ -199,11 +269,56 @@ mod tests {
         assert_eq!(default_folder.unwrap(), "assets".to_string());
     }
 
+    #[test]
+    fn test_template_get_default_folder_dialects() {
+        let template: ScribeTemplate = ScribeTemplate::from_str(
+            "+++\ntitle = \"{{ TITLE }}\"\ndefault_folder = \"assets\"\n+++\n\n",
+        );
+        assert_eq!(template.get_default_folder(), Some("assets".to_string()));
+
+        let template: ScribeTemplate = ScribeTemplate::from_str(
+            "{\n  \"title\": \"{{ TITLE }}\",\n  \"default_folder\": \"assets\"\n}\n\n",
+        );
+        assert_eq!(template.get_default_folder(), Some("assets".to_string()));
+    }
+
     #[test]
     fn test_template_library_load() {
         let _library = ScribeTemplateLibrary::load();
     }
 
+    #[test]
+    fn test_template_library_builtin_formats() {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert("TITLE".to_string(), "Test Title".to_string());
+        params.insert("DATE".to_string(), "2022-01-01 12:31 PM".to_string());
+        params.insert("TAGS".to_string(), r#""tag1", "tag2""#.to_string());
+
+        for format in [
+            FrontMatterFormat::Yaml,
+            FrontMatterFormat::Toml,
+            FrontMatterFormat::Json,
+        ] {
+            let library = ScribeTemplateLibrary::load_with_format(format);
+            let filled = library
+                .get_template("basic")
+                .unwrap()
+                .fill(&params)
+                .unwrap();
+
+            let (block, _) = FrontMatter::split(&filled);
+            assert_eq!(block.unwrap().format, format);
+
+            let front_matter = FrontMatter::parse(&filled).unwrap();
+            assert_eq!(front_matter.get_str("title"), Some("Test Title"));
+            assert!(front_matter.get("date").unwrap().as_date().is_some());
+            assert_eq!(
+                front_matter.get("tags").unwrap().as_list().unwrap().len(),
+                2
+            );
+        }
+    }
+
     #[test]
     fn test_template_library_list_templates() {
         let library = ScribeTemplateLibrary::load();